@@ -0,0 +1,112 @@
+use crate::asynch::datasource::Error;
+use crate::asynch::repository::LectureRepository;
+use crate::asynch::sources::*;
+use crate::lectures::config::Config;
+use crate::lectures::entities::{Lecture, StaticDegree};
+use crate::lectures::loaded::LoadedLectures;
+use crate::Degrees;
+use futures::future::try_join_all;
+use std::ops::Deref;
+
+/// Loads lectures from a repository of async data sources. Loaded lectures are queried through
+/// [`LoadedLectures`], which the client dereferences to.
+pub struct LectureClient<'a> {
+    repository: LectureRepository<'a>,
+    lectures: LoadedLectures,
+    degrees: Vec<&'static StaticDegree>,
+}
+
+impl<'a> LectureClient<'a> {
+    pub fn from_config(config: Config) -> Self {
+        let mut repository = LectureRepository::new();
+        if let Some(path) = config.get_cache_path() {
+            let mut cache = FSDataSource::new(path.to_string());
+            // Outdated lectures are still better than none when scraping is disabled
+            if let (Some(ttl), false) = (config.cache_ttl, config.offline) {
+                cache = cache.ttl(ttl);
+            }
+            repository.add_source(cache);
+        }
+        if !config.offline {
+            repository.add_readonly_source(ScraperSource::from_config(&config));
+        }
+
+        LectureClient {
+            degrees: config.enabled_degrees(),
+            ..Self::from_repository(repository)
+        }
+    }
+
+    /// Creates a client which serves lectures from a custom repository
+    pub fn from_repository(repository: LectureRepository<'a>) -> Self {
+        LectureClient {
+            repository,
+            lectures: LoadedLectures::default(),
+            degrees: Degrees::all().iter().collect(),
+        }
+    }
+
+    /// The degrees which are loaded by `init`
+    pub fn degrees(&self) -> &[&'static StaticDegree] {
+        &self.degrees
+    }
+
+    /// Call after creating LectureClient to ensure lectures were loaded.
+    /// Lectures for all enabled degrees are loaded concurrently. If any degree fails to load,
+    /// the first error is returned and no lectures are replaced.
+    pub async fn init(&mut self) -> Result<(), Error> {
+        let repository = &self.repository;
+        let loaded = try_join_all(self.degrees.iter().map(|degree| async move {
+            repository.load_and_update(degree).await.map(|lectures| (*degree, lectures))
+        }))
+        .await?;
+
+        for (degree, lectures) in loaded {
+            self.lectures.insert(degree, lectures);
+        }
+        Ok(())
+    }
+
+    pub async fn initialized(mut self) -> Self {
+        let _ = self.init().await;
+        self
+    }
+
+    /// Loads the lectures of a single degree, e.g. to initialize only the degrees that are needed
+    pub async fn load_lectures(&mut self, degree: &'static StaticDegree) -> Result<&[Lecture], Error> {
+        let lectures = self.repository.load_and_update(degree).await?;
+        Ok(self.lectures.insert(degree, lectures))
+    }
+}
+
+impl Deref for LectureClient<'_> {
+    type Target = LoadedLectures;
+
+    fn deref(&self) -> &LoadedLectures {
+        &self.lectures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asynch::datasource::ReadWriteDataSource;
+    use async_std::task;
+
+    #[test]
+    fn replaces_no_lectures_if_a_degree_fails_to_load() {
+        let lecture = Lecture {
+            title: String::from("Datenbanksysteme I"),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            ..Default::default()
+        };
+        let source = InMemoryDataSource::new();
+        task::block_on(source.save_lectures(&Degrees::ITSE_BA, &[lecture])).unwrap();
+        let mut client = LectureClient::from_repository(LectureRepository::new().source(source));
+
+        assert!(task::block_on(client.init()).is_err());
+        assert!(client.lectures(&Degrees::ITSE_BA).is_empty());
+        assert_eq!(task::block_on(client.load_lectures(&Degrees::ITSE_BA)).unwrap().len(), 1);
+        assert_eq!(client.lecture("dbs1").unwrap().title, "Datenbanksysteme I");
+    }
+}
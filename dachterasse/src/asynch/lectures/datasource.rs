@@ -1,5 +1,6 @@
 use crate::{StaticDegree, Lecture};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 pub type Error = String;
 
 pub type LoadResult = Result<Vec<Lecture>, Error>;
@@ -8,9 +9,32 @@ pub type SaveResult = Result<(), Error>;
 #[async_trait]
 pub trait ReadOnlyDataSource: Send + Sync {
     async fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult;
+
+    /// When the lectures of a degree were last written to the source, e.g. when they were scraped and cached.
+    /// `None` if the source does not know.
+    async fn modified(&self, _degree: &'static StaticDegree) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// A short name of the source for diagnostics, e.g. `InMemoryDataSource`
+    fn name(&self) -> &'static str {
+        short_type_name::<Self>()
+    }
 }
 
 #[async_trait]
 pub trait ReadWriteDataSource: ReadOnlyDataSource {
     async fn save_lectures(&self, degree: &'static StaticDegree, lectures: &[Lecture]) -> SaveResult;
+
+    /// Deletes the stored lectures of a degree, so that they are loaded from other sources again
+    async fn clear_lectures(&self, _degree: &'static StaticDegree) -> SaveResult {
+        Err(String::from("Clearing is not supported by this data source"))
+    }
+}
+
+/// The name of a type without its module path and generic parameters
+pub fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
 }
@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use chrono::{DateTime, Utc};
+
 use crate::asynch::datasource::{Error, ReadOnlyDataSource, ReadWriteDataSource};
 use crate::lectures::entities::StaticDegree;
 
@@ -6,16 +11,90 @@ use crate::lectures::entities::Lecture;
 /// A lecture repository which is responsible for keeping lecture information in sync across multiple data sources
 #[derive(Default)]
 pub struct LectureRepository<'a> {
-    sources: Vec<Box<dyn ReadWriteDataSource + 'a>>,
-    read_only_sources: Vec<Box<dyn ReadOnlyDataSource + 'a>>,
+    sources: Vec<Tracked<Box<dyn ReadWriteDataSource + 'a>>>,
+    read_only_sources: Vec<Tracked<Box<dyn ReadOnlyDataSource + 'a>>>,
+}
+
+/// Lectures of a degree together with the time at which they were scraped or, if they were loaded from
+/// a read-write source, last written to it
+#[derive(Clone, Debug)]
+pub struct Loaded {
+    pub lectures: Vec<Lecture>,
+    pub modified: DateTime<Utc>,
+}
+
+/// Health of a data source as observed by the repository
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SourceHealth {
+    pub name: &'static str,
+    pub read_only: bool,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<SourceError>,
+    /// When the lectures of each degree were last saved to the source or, for read-only sources,
+    /// loaded from it, by degree id
+    pub updated: HashMap<&'static str, DateTime<Utc>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SourceError {
+    pub time: DateTime<Utc>,
+    pub message: String,
+}
+
+impl SourceHealth {
+    fn new(name: &'static str, read_only: bool) -> Self {
+        SourceHealth {
+            name,
+            read_only,
+            last_success: None,
+            last_error: None,
+            updated: HashMap::new(),
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.last_success = Some(Utc::now());
+    }
+
+    fn updated(&mut self, degree: &'static StaticDegree) {
+        self.succeeded();
+        self.updated.insert(degree.id, Utc::now());
+    }
+
+    fn failed(&mut self, message: &str) {
+        self.last_error = Some(SourceError {
+            time: Utc::now(),
+            message: message.to_owned(),
+        });
+    }
+}
+
+struct Tracked<S> {
+    source: S,
+    health: Mutex<SourceHealth>,
+}
+
+impl<S> Tracked<S> {
+    fn new(source: S, name: &'static str, read_only: bool) -> Self {
+        Tracked {
+            source,
+            health: Mutex::new(SourceHealth::new(name, read_only)),
+        }
+    }
+
+    fn health(&self) -> MutexGuard<'_, SourceHealth> {
+        self.health.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl<'a> LectureRepository<'a> {
     /// Create a new repository which serves lectures from its specified data sources
     ///
     /// * `sources` - Data sources from where the repository tries to serve the lecture data.
-    /// Loading the data will be attempted in the order in which data sources appear in the vector
-    /// until one data source returns a successful result.
+    ///   Loading the data will be attempted in the order in which data sources appear in the vector
+    ///   until one data source returns a successful result.
     pub fn new() -> Self {
         LectureRepository {
             sources: Vec::new(),
@@ -27,7 +106,8 @@ impl<'a> LectureRepository<'a> {
     /// Loading data will be attempted in the order in which data sources are added to the repository
     /// until one data source returns a successful result.
     pub fn add_source(&mut self, source: impl ReadWriteDataSource + 'a) {
-        self.sources.push(Box::new(source));
+        let name = source.name();
+        self.sources.push(Tracked::new(Box::new(source), name, false));
     }
 
     /// Builder function to easily append additional data sources to repository
@@ -38,7 +118,8 @@ impl<'a> LectureRepository<'a> {
 
     /// Adds a read-only source to this repository. Read-only sources will only be read if requests to all sources were unsuccessful.
     pub fn add_readonly_source(&mut self, source: impl ReadOnlyDataSource + 'a) {
-        self.read_only_sources.push(Box::new(source));
+        let name = source.name();
+        self.read_only_sources.push(Tracked::new(Box::new(source), name, true));
     }
 
     /// Builder function to easily append additional read-only data sources to repository.
@@ -47,39 +128,121 @@ impl<'a> LectureRepository<'a> {
         self
     }
 
-    /// Load lectures from repository data sources and write them to read-write sources
+    /// Load lectures from repository data sources and write them to the read-write sources which did not
+    /// return them, i.e. the ones which were tried before. The source which returned the lectures is not
+    /// written to, so that the age of its lectures is kept.
     pub async fn load_and_update(&self, degree: &'static StaticDegree) -> Result<Vec<Lecture>, Error> {
-        match self.try_loading(degree).await {
-            Some(lectures) => {
-                for rw in &self.sources {
-                    if let Err(e) = rw.save_lectures(degree, &lectures).await {
-                        eprintln!("Error saving lecture to some datasource with error: {}", e);
-                    }
-                }
+        Ok(self.load(degree).await?.lectures)
+    }
+
+    /// Like [`LectureRepository::load_and_update`], but also returns when the lectures were scraped or
+    /// written to the source which returned them. Sources which do not know this are assumed to have
+    /// been written just now.
+    pub async fn load(&self, degree: &'static StaticDegree) -> Result<Loaded, Error> {
+        let (lectures, loaded_from) = match first_loaded(&self.sources, degree).await {
+            Some(loaded) => loaded,
+            None => match first_loaded(&self.read_only_sources, degree).await {
+                Some((lectures, _)) => (lectures, self.sources.len()),
+                None => return Err(format!("No source returned lectures for degree {}", degree.name)),
+            },
+        };
+
+        let modified = match self.sources.get(loaded_from) {
+            Some(tracked) => tracked.source.modified(degree).await,
+            None => None,
+        };
+        save(&self.sources[..loaded_from], degree, &lectures).await;
+        Ok(Loaded {
+            lectures,
+            modified: modified.unwrap_or_else(Utc::now),
+        })
+    }
+
+    /// Loads lectures from the read-only sources only, e.g. to scrape them again,
+    /// and replaces the lectures stored in the read-write sources with them
+    pub async fn refresh(&self, degree: &'static StaticDegree) -> Result<Vec<Lecture>, Error> {
+        match first_loaded(&self.read_only_sources, degree).await {
+            Some((lectures, _)) => {
+                save(&self.sources, degree, &lectures).await;
                 Ok(lectures)
             }
             None => Err(format!(
-                "No source returned lectures for degree {}",
+                "No read-only source returned lectures for degree {}",
                 degree.name
             )),
         }
     }
 
-    async fn try_loading(&self, degree: &'static StaticDegree) -> Option<Vec<Lecture>> {
-        for source in &self.sources {
-            match source.load_lectures(degree).await {
-                Ok(result) => return Some(result),
-                Err(_) => continue,
+    /// Deletes the lectures of a degree from all read-write sources
+    pub async fn clear(&self, degree: &'static StaticDegree) -> Result<(), Error> {
+        let mut errors = Vec::new();
+        for tracked in &self.sources {
+            match tracked.source.clear_lectures(degree).await {
+                Ok(()) => {
+                    let mut health = tracked.health();
+                    health.succeeded();
+                    health.updated.remove(degree.id);
+                }
+                Err(err) => {
+                    tracked.health().failed(&err);
+                    errors.push(err);
+                }
             }
         }
 
-        for source in &self.read_only_sources {
-            match source.load_lectures(degree).await {
-                Ok(result) => return Some(result),
-                Err(_) => continue,
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    /// Health of all sources, read-write sources first, each in the order in which they were added
+    pub fn health(&self) -> Vec<SourceHealth> {
+        let sources = self.sources.iter().map(|tracked| tracked.health().clone());
+        let read_only = self.read_only_sources.iter().map(|tracked| tracked.health().clone());
+        sources.chain(read_only).collect()
+    }
+}
+
+/// Saves the lectures to the given sources. Failures are recorded in the health of the failing source,
+/// so that they do not prevent serving the lectures or saving them to the other sources.
+async fn save(
+    sources: &[Tracked<Box<dyn ReadWriteDataSource + '_>>],
+    degree: &'static StaticDegree,
+    lectures: &[Lecture],
+) {
+    for tracked in sources {
+        match tracked.source.save_lectures(degree, lectures).await {
+            Ok(()) => tracked.health().updated(degree),
+            Err(e) => {
+                eprintln!("Error saving lecture to some datasource with error: {}", e);
+                tracked.health().failed(&e);
             }
         }
+    }
+}
 
-        None
+/// Loads lectures from the first source which returns them, together with the index of that source.
+/// Sources which fail are recorded as unhealthy.
+async fn first_loaded<S: ReadOnlyDataSource + ?Sized>(
+    sources: &[Tracked<Box<S>>],
+    degree: &'static StaticDegree,
+) -> Option<(Vec<Lecture>, usize)> {
+    for (index, tracked) in sources.iter().enumerate() {
+        match tracked.source.load_lectures(degree).await {
+            Ok(lectures) => {
+                let mut health = tracked.health();
+                if health.read_only {
+                    health.updated(degree);
+                } else {
+                    health.succeeded();
+                }
+                return Some((lectures, index));
+            }
+            Err(err) => tracked.health().failed(&err),
+        }
     }
+
+    None
 }
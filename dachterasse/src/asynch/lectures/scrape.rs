@@ -1,105 +1,132 @@
-use crate::lectures::entities::StaticDegree;
+use crate::lectures::config::Config;
 use crate::lectures::entities::Lecture;
-use regex::Regex;
-use reqwest::Client;
-use scraper::element_ref::Text;
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+use crate::lectures::entities::StaticDegree;
+use crate::lectures::pages::CachedPage;
+use crate::lectures::politeness::{RobotsTxt, ScrapeError};
+use crate::lectures::site::{self, Site};
+use async_std::task;
+use futures::{StreamExt, TryStreamExt};
+use reqwest::{Client, RequestBuilder, Response};
+use std::sync::Arc;
 
-#[derive(Default)]
 pub struct LectureScraper {
     client: Client,
+    site: Site,
 }
 
-pub type Error = reqwest::Error;
+pub type Error = ScrapeError;
+
+impl Default for LectureScraper {
+    fn default() -> Self {
+        LectureScraper::new()
+    }
+}
 
 impl LectureScraper {
     pub fn new() -> Self {
+        LectureScraper::from_config(&Config::default())
+    }
+
+    /// Creates a scraper using the base URL, user agent, timeout, concurrency and politeness settings of the config.
+    /// If a cache path is configured, validators of scraped pages are kept there for conditional requests.
+    pub fn from_config(config: &Config) -> Self {
+        let client = Client::builder()
+            .user_agent(config.user_agent_header())
+            .timeout(config.timeout)
+            .build()
+            .expect("Could not initialize HTTP client");
+
         LectureScraper {
-            client: Client::new(),
+            client,
+            site: Site::from_config(config),
         }
     }
 
-    // TODO: Extract document fetching and parsing into separate method for better testability
+    /// Fetches the lectures listed on the overview page of a degree, without details. If the page did not
+    /// change since it was last fetched, the previously parsed lectures are returned.
     pub async fn fetch_lectures(&self, degree: &StaticDegree) -> Result<Vec<Lecture>, Error> {
-        let url = degree.url;
-        let document = get_text(url, &self.client).await?;
-        let fragment = Html::parse_document(&document);
-        let selector = Selector::parse("a.courselink").unwrap();
-        let link_regex = Regex::new(r"/studium.*\.html").unwrap();
-
-        Ok(fragment
-            .select(&selector)
-            .map(|element| Lecture {
-                title: clean(element.text()),
-                url: String::from("https://hpi.de")
-                    + &link_regex.captures(&*element.html()).unwrap()[0],
-                categories: None,
-            })
-            .collect())
+        let url = self.site.overview_url(degree);
+        if !self.robots().await?.is_allowed(&url) {
+            return Err(ScrapeError::Disallowed(url));
+        }
+        let cached = self.site.pages.overview(&url);
+        let response = self
+            .send(
+                self.client
+                    .get(&url)
+                    .headers(cached.as_ref().map(CachedPage::conditions).unwrap_or_default()),
+            )
+            .await?;
+
+        if let Some(lectures) = site::unchanged(&url, response.status(), cached)? {
+            return Ok(lectures);
+        }
+        let headers = response.headers().clone();
+        Ok(self.site.set_overview(&url, &headers, &response.text().await?))
     }
 
-    // TODO: Extract document fetching and parsing into separate method for better testability
+    /// Fetches all lectures of a degree including their details, with up to `concurrency` requests at the same time
     pub async fn fetch_lecture_details(&self, degree: &StaticDegree) -> Result<Vec<Lecture>, Error> {
-        let mut lectures = self.fetch_lectures(degree).await?;
-        for mut lecture in &mut lectures {
-            let document = get_text(&lecture.url, &self.client).await?;
-
-            if let Some(inner_fragment) = self.scrape_modules(document.as_str(), degree.name) {
-                let module_list = Html::parse_fragment(inner_fragment.as_str());
-                let item_selector = Selector::parse("li").unwrap();
-                let categories: Vec<(String, Vec<String>)> = module_list
-                    .select(&item_selector)
-                    .map(|element| {
-                        (
-                            clean(element.text()),
-                            Html::parse_fragment(element.inner_html().as_str())
-                                .select(&item_selector)
-                                .map(|child| clean(child.text()))
-                                .collect::<Vec<_>>(),
-                        )
-                    })
-                    .filter(|(_, children)| !children.is_empty())
-                    .collect();
-
-                let mut category_map = HashMap::<String, Vec<String>>::new();
-                for mut category in categories {
-                    category_map
-                        .entry(category.0)
-                        .or_insert(Vec::new())
-                        .append(&mut category.1);
-                }
-                lecture.categories = Some(category_map);
-            }
-        }
+        let lectures = self.fetch_lectures(degree).await?;
+
+        let lectures = futures::stream::iter(lectures)
+            .map(|mut lecture| async move {
+                self.fetch_details(degree, &mut lecture).await?;
+                Ok::<_, Error>(lecture)
+            })
+            .buffered(self.site.concurrency)
+            .try_collect()
+            .await?;
 
+        self.site.save_pages();
         Ok(lectures)
     }
 
-    fn scrape_modules(&self, document: &str, degree: &str) -> Option<String> {
-        let fragment = Html::parse_document(document);
-        let selector = Selector::parse("div.tx_dscclipclap").unwrap();
-        let header_selector = Selector::parse("div.tx_dscclipclap_header").unwrap();
-
-        fragment
-            .select(&selector)
-            .find(|element| {
-                Html::parse_fragment(element.inner_html().as_str())
-                    .select(&header_selector)
-                    .any(|inner| clean(inner.text()) == degree)
-            })
-            .map(|optional| optional.inner_html())
+    /// Fetches the details of a lecture, which are only parsed again if its page changed.
+    /// Lectures whose page is disallowed by robots.txt are left without details.
+    async fn fetch_details(&self, degree: &StaticDegree, lecture: &mut Lecture) -> Result<(), Error> {
+        if !self.robots().await?.is_allowed(&lecture.url) {
+            return Ok(());
+        }
+        let cached = self.site.pages.details(&lecture.url, degree.name);
+        let response = self
+            .send(
+                self.client
+                    .get(&lecture.url)
+                    .headers(cached.as_ref().map(CachedPage::conditions).unwrap_or_default()),
+            )
+            .await?;
+
+        let details = match site::unchanged(&lecture.url, response.status(), cached)? {
+            Some(details) => details,
+            None => {
+                let headers = response.headers().clone();
+                self.site.set_details(&lecture.url, degree, &headers, &response.text().await?)
+            }
+        };
+        details.apply_to(lecture);
+        Ok(())
     }
-}
 
-fn clean(text: Text) -> String {
-    String::from(clean_str(text))
-}
+    /// The site's robots.txt, which is fetched if it is not cached yet
+    async fn robots(&self) -> Result<Arc<RobotsTxt>, Error> {
+        let url = match self.site.robots() {
+            Ok(robots) => return Ok(robots),
+            Err(url) => url,
+        };
 
-fn clean_str(text: Text) -> &str {
-    text.collect::<Vec<_>>().first().unwrap().trim()
-}
+        let response = self.send(self.client.get(&url)).await?;
+        let document = if self.site.has_robots(&url, response.status())? {
+            Some(response.text().await?)
+        } else {
+            None
+        };
+        Ok(self.site.set_robots(document.as_deref()))
+    }
 
-async fn get_text(url: &str, client: &Client) -> Result<String, reqwest::Error> {
-    client.get(url).send().await?.text().await
+    /// Sends the request once the rate limit allows it
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        task::sleep(self.site.limiter.reserve()).await;
+        Ok(request.send().await?)
+    }
 }
@@ -0,0 +1,138 @@
+use crate::asynch::datasource::{LoadResult, ReadOnlyDataSource, ReadWriteDataSource, SaveResult};
+use crate::{Degrees, Lecture, StaticDegree};
+use async_std::fs;
+use async_std::io;
+use async_std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+pub struct FSDataSource {
+    caches: HashMap<&'static StaticDegree, FileSystemCache>,
+    ttl: Option<Duration>,
+}
+
+#[async_trait]
+impl ReadOnlyDataSource for FSDataSource {
+    async fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult {
+        if let Some(ttl) = self.ttl {
+            let age = self.cache_for_degree(degree).age().await;
+            if age.is_none_or(|age| age > ttl) {
+                return Err(format!("Cache for degree {} is missing or outdated", degree.id));
+            }
+        }
+
+        self.cache_for_degree(degree)
+            .load_lectures()
+            .await
+            .map_err(|err| format!("Could not load cache for degree {} due to {}", degree.id, err))
+    }
+
+    async fn modified(&self, degree: &'static StaticDegree) -> Option<DateTime<Utc>> {
+        self.cache_for_degree(degree).modified().await.map(DateTime::from)
+    }
+}
+
+#[async_trait]
+impl ReadWriteDataSource for FSDataSource {
+    async fn save_lectures(&self, degree: &'static StaticDegree, lectures: &[Lecture]) -> SaveResult {
+        self.cache_for_degree(degree)
+            .save_lectures(lectures)
+            .await
+            .map_err(|err| format!("Could not save cache for degree {} due to {}", degree.id, err))
+    }
+
+    async fn clear_lectures(&self, degree: &'static StaticDegree) -> SaveResult {
+        self.cache_for_degree(degree)
+            .clear()
+            .await
+            .map_err(|err| format!("Could not clear cache for degree {} due to {}", degree.id, err))
+    }
+}
+
+impl FSDataSource {
+    pub fn new(path: String) -> Self {
+        let mut caches = HashMap::new();
+
+        for degree in Degrees::all() {
+            caches.insert(
+                degree,
+                FileSystemCache {
+                    path: Path::new(&path).join(degree.id),
+                },
+            );
+        }
+
+        FSDataSource { caches, ttl: None }
+    }
+
+    /// Treats cached lectures which are older than `ttl` as missing, so that they are loaded from other sources again
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn cache_for_degree(&self, degree: &'static StaticDegree) -> &FileSystemCache {
+        &self.caches[degree]
+    }
+}
+
+struct FileSystemCache {
+    path: PathBuf,
+}
+
+impl FileSystemCache {
+    async fn load_lectures(&self) -> io::Result<Vec<Lecture>> {
+        load_cache_from(&self.path).await
+    }
+
+    async fn save_lectures(&self, lectures: &[Lecture]) -> io::Result<()> {
+        save_cache_to(&self.path, lectures).await
+    }
+
+    async fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(with_extension(&self.path, "json")).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Time since the cache was last written or `None` if nothing was cached yet
+    async fn age(&self) -> Option<Duration> {
+        Some(self.modified().await?.elapsed().unwrap_or_default())
+    }
+
+    /// Time at which the cache was last written or `None` if nothing was cached yet
+    async fn modified(&self) -> Option<SystemTime> {
+        let metadata = fs::metadata(with_extension(&self.path, "json")).await.ok()?;
+        metadata.modified().ok()
+    }
+}
+
+/// Attempts to load cached lecture information from a JSON file
+async fn load_cache_from(path: &Path) -> io::Result<Vec<Lecture>> {
+    let content = fs::read(with_extension(path, "json")).await?;
+    let cache = serde_json::from_slice(&content)?;
+    Ok(cache)
+}
+
+/// Serializes cache to JSON and writes it to a file
+async fn save_cache_to(path: &Path, cache: &[Lecture]) -> io::Result<()> {
+    create_parent_directory(path).await?;
+    let content = serde_json::to_vec(cache)?;
+    fs::write(with_extension(path, "json"), content).await
+}
+
+async fn create_parent_directory(path: &Path) -> io::Result<()> {
+    if let Some(directories) = path.parent() {
+        fs::create_dir_all(directories).await?;
+    }
+    Ok(())
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut buf = path.to_path_buf();
+    buf.set_extension(extension);
+    buf
+}
@@ -1,6 +1,6 @@
 use crate::asynch::datasource::{LoadResult, ReadOnlyDataSource};
 use crate::asynch::scrape::LectureScraper;
-use crate::StaticDegree;
+use crate::{Config, StaticDegree};
 use async_trait::async_trait;
 
 #[derive(Default)]
@@ -14,6 +14,12 @@ impl ScraperSource {
             scraper: LectureScraper::new(),
         }
     }
+
+    pub fn from_config(config: &Config) -> Self {
+        ScraperSource {
+            scraper: LectureScraper::from_config(config),
+        }
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,283 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use dachterasse::export::Format;
+use dachterasse::{Degrees, Language, StaticDegree};
+
+#[derive(Parser)]
+#[command(
+    name = "dachterasse",
+    version,
+    about = "Browse the lectures of the HPI lecture overview site",
+    after_help = "Exit codes: 0 success, 1 lectures could not be loaded, 2 invalid arguments, 3 file access failed"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Degree to use, given by its id (e.g. itse-ba), a comma-separated list of ids or 'all'.
+    /// If omitted, the degree is prompted for when running in a terminal.
+    #[arg(long, short, global = true, value_name = "ID|all", value_parser = parse_degrees)]
+    pub degree: Option<DegreeSelection>,
+
+    /// Config file to use instead of the one in the user's config directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Directory in which lectures are cached. Overrides the config file and DACHTERASSE_CACHE_DIR. Default is 'cache'
+    #[arg(long, global = true, value_name = "PATH")]
+    pub cache_dir: Option<String>,
+
+    /// Output format: text, csv, markdown, html, json, json-pretty or ics
+    #[arg(long, short, global = true, value_parser = parse_format)]
+    pub format: Option<OutputFormat>,
+
+    /// Print results and errors as a single JSON document
+    #[arg(long, global = true, conflicts_with_all = ["format", "ndjson"])]
+    pub json: bool,
+
+    /// Print results and errors as newline-delimited JSON, one object per line
+    #[arg(long, global = true, conflicts_with = "format")]
+    pub ndjson: bool,
+
+    /// Only use cached lectures and never scrape the lecture overview site
+    #[arg(long, global = true)]
+    pub offline: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Displays titles of all lectures
+    Overview,
+    /// Shows details of all lectures
+    All,
+    /// Searches titles, descriptions and modules of all lectures, e.g. 'dachterasse search machine learning'
+    Search {
+        #[arg(required = true)]
+        text: Vec<String>,
+    },
+    /// Lists lectures matching all given criteria, e.g. 'dachterasse filter --module Vertiefungsgebiete --ects 6'
+    Filter(FilterArgs),
+    /// Scrapes the lectures of all or the selected degrees into the cache
+    Init,
+    /// Scrapes the lectures of all or the selected degrees again if their cache is outdated
+    Refresh {
+        /// Age in hours after which a degree's cache is outdated. Default is the configured cache TTL or 24 hours
+        #[arg(long, value_name = "HOURS")]
+        max_age: Option<u64>,
+    },
+    /// Shows or clears the lecture cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Exports lectures, e.g. 'dachterasse export --format csv --output lectures.csv'
+    Export {
+        /// File to write the export to. Default is stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Browses lectures in an interactive terminal UI
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Directory in which study plans are stored, one file per degree
+        #[arg(long, value_name = "PATH", default_value = "plans")]
+        plan_dir: PathBuf,
+    },
+}
+
+#[derive(Args)]
+pub struct FilterArgs {
+    /// Module in which lectures can be taken. Can be given multiple times to match any of the modules
+    #[arg(long, short)]
+    pub module: Vec<String>,
+    /// Category in which lectures can be taken, within the given modules if any.
+    /// Can be given multiple times to match any of the categories
+    #[arg(long, short)]
+    pub category: Vec<String>,
+    /// Text contained in the lecture title
+    #[arg(long, short)]
+    pub title: Option<String>,
+    /// Name of one of the lecturers
+    #[arg(long, short)]
+    pub lecturer: Option<String>,
+    /// ECTS of the lecture, either a number or a range like 3-6
+    #[arg(long, value_parser = parse_ects)]
+    pub ects: Option<RangeInclusive<u8>>,
+    /// Teaching language: de or en
+    #[arg(long, value_parser = parse_language)]
+    pub language: Option<Language>,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Shows age, size and lecture count of the cache of each degree
+    Status,
+    /// Deletes the cache of all or the selected degrees
+    Clear,
+}
+
+impl Cli {
+    /// The output requested with `--format`, `--json` or `--ndjson`
+    pub fn output(&self) -> Output {
+        match (self.json_mode(), self.format) {
+            (Some(mode), _) => Output::Json(mode),
+            (None, Some(OutputFormat::Export(format))) => Output::Export(format),
+            (None, _) => Output::Text,
+        }
+    }
+
+    pub fn json_mode(&self) -> Option<JsonMode> {
+        if self.json {
+            Some(JsonMode::Document)
+        } else if self.ndjson {
+            Some(JsonMode::Lines)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Overview => "overview",
+            Command::All => "all",
+            Command::Search { .. } => "search",
+            Command::Filter(_) => "filter",
+            Command::Init => "init",
+            Command::Refresh { .. } => "refresh",
+            Command::Cache {
+                command: CacheCommand::Status,
+            } => "cache status",
+            Command::Cache {
+                command: CacheCommand::Clear,
+            } => "cache clear",
+            Command::Export { .. } => "export",
+            #[cfg(feature = "tui")]
+            Command::Tui { .. } => "tui",
+        }
+    }
+}
+
+/// The degrees selected with `--degree`
+#[derive(Clone)]
+pub struct DegreeSelection(pub Vec<&'static StaticDegree>);
+
+fn parse_degrees(value: &str) -> Result<DegreeSelection, String> {
+    if value.eq_ignore_ascii_case("all") {
+        return Ok(DegreeSelection(Degrees::all().iter().collect()));
+    }
+
+    value
+        .split(',')
+        .map(|id| {
+            let id = id.trim();
+            Degrees::by_id(id).ok_or_else(|| {
+                let ids = Degrees::all().iter().map(|degree| degree.id).collect::<Vec<_>>();
+                format!("Unknown degree '{}'. Known degrees are: {}", id, ids.join(", "))
+            })
+        })
+        .collect::<Result<_, _>>()
+        .map(DegreeSelection)
+}
+
+fn parse_ects(value: &str) -> Result<RangeInclusive<u8>, String> {
+    let parse = |ects: &str| {
+        ects.trim()
+            .parse::<u8>()
+            .map_err(|_| format!("'{}' is not a valid number of ECTS", ects.trim()))
+    };
+
+    match value.split_once(['-', ':']).or_else(|| value.split_once("..")) {
+        Some((min, max)) => {
+            let (min, max) = (parse(min)?, parse(max.trim_start_matches('='))?);
+            if min > max {
+                return Err(format!("The ECTS range {}-{} is empty, since {} is more than {}", min, max, min, max));
+            }
+            Ok(min..=max)
+        }
+        None => parse(value).map(|ects| ects..=ects),
+    }
+}
+
+fn parse_language(value: &str) -> Result<Language, String> {
+    match value.to_lowercase().as_str() {
+        "de" | "german" | "deutsch" => Ok(Language::German),
+        "en" | "english" | "englisch" => Ok(Language::English),
+        _ => Err(format!("Unknown language '{}'. Use de or en", value)),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum JsonMode {
+    /// A single JSON array or object
+    Document,
+    /// One JSON object per line
+    Lines,
+}
+
+#[derive(Clone, Copy)]
+pub enum Output {
+    Text,
+    Export(Format),
+    Json(JsonMode),
+}
+
+#[derive(Clone, Copy)]
+pub enum OutputFormat {
+    /// Human readable text
+    Text,
+    Export(Format),
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, String> {
+    if value.eq_ignore_ascii_case("text") {
+        Ok(OutputFormat::Text)
+    } else {
+        value.parse().map(OutputFormat::Export)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(["dachterasse"].iter().chain(args))
+    }
+
+    #[test]
+    fn parses_subcommands_and_global_options() {
+        let cli = parse(&[
+            "filter", "-m", "Vertiefungsgebiete", "--ects", "3-6", "--language", "en", "-d", "itse-ba,itse-ma",
+        ])
+        .unwrap();
+        let Command::Filter(args) = &cli.command else {
+            panic!("expected filter command");
+        };
+        assert_eq!(args.module, vec!["Vertiefungsgebiete"]);
+        assert_eq!(args.ects, Some(3..=6));
+        assert_eq!(args.language, Some(Language::English));
+        assert_eq!(cli.degree.unwrap().0, vec![&Degrees::ITSE_BA, &Degrees::ITSE_MA]);
+
+        let cli = parse(&["cache", "status", "--ndjson"]).unwrap();
+        assert_eq!(cli.command.name(), "cache status");
+        assert!(matches!(cli.output(), Output::Json(JsonMode::Lines)));
+
+        let cli = parse(&["export", "--format", "csv", "--output", "lectures.csv"]).unwrap();
+        assert!(matches!(cli.output(), Output::Export(Format::Csv)));
+        assert!(matches!(parse(&["overview", "--format", "text"]).unwrap().output(), Output::Text));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&["overview", "--degree", "unknown"]).is_err());
+        assert!(parse(&["filter", "--ects", "six"]).is_err());
+        assert!(parse(&["filter", "--ects", "6-3"]).is_err());
+        assert!(parse(&["filter", "--language", "fr"]).is_err());
+        assert!(parse(&["overview", "--json", "--format", "csv"]).is_err());
+        assert!(parse(&["search"]).is_err());
+    }
+}
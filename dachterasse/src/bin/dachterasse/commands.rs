@@ -0,0 +1,440 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::Duration;
+
+use dachterasse::export::Format;
+use dachterasse::query::{Filter, Query};
+use dachterasse::sources::FSDataSource;
+use dachterasse::{Config, Degrees, Lecture, LectureClient, StaticDegree};
+
+use crate::args::{CacheCommand, Cli, Command, FilterArgs, JsonMode, Output};
+use crate::error::CliError;
+use crate::output::*;
+
+/// Age after which `refresh` scrapes a degree again if no cache TTL is configured
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Cache directory used if neither `--cache-dir` nor the config file or environment set one
+const DEFAULT_CACHE_DIR: &str = "cache";
+
+pub fn run(cli: &Cli) -> Result<(), CliError> {
+    let config = &config(cli)?;
+
+    match &cli.command {
+        Command::Overview => show_overview(cli, config),
+        Command::All => show_details(cli, config),
+        Command::Search { text } => search(cli, config, &text.join(" ")),
+        Command::Filter(args) => filter(cli, config, args),
+        Command::Init => init(cli, config),
+        Command::Refresh { max_age: hours } => refresh(cli, config, max_age(*hours, config)?),
+        Command::Cache {
+            command: CacheCommand::Status,
+        } => show_cache_status(cli, config),
+        Command::Cache {
+            command: CacheCommand::Clear,
+        } => clear_cache(cli, config),
+        Command::Export { output } => export(cli, config, output.as_deref()),
+        #[cfg(feature = "tui")]
+        Command::Tui { plan_dir } => crate::tui::run(cli, config, plan_dir),
+    }
+}
+
+fn show_overview(cli: &Cli, config: &Config) -> Result<(), CliError> {
+    let degrees = selected_degrees(cli)?;
+    let client = client(config, &degrees)?;
+    let lectures = lectures_by_degree(&client, &degrees);
+
+    let mut out = io::stdout().lock();
+    match cli.output() {
+        Output::Json(mode) => Ok(write_json(&mut out, mode, degree_lectures(&lectures))?),
+        Output::Export(format) => export_lectures(format, &degrees, &lectures, &mut out),
+        Output::Text => Ok(print_lectures(&mut out, &lectures)?),
+    }
+}
+
+fn show_details(cli: &Cli, config: &Config) -> Result<(), CliError> {
+    let degrees = selected_degrees(cli)?;
+    let client = client(config, &degrees)?;
+    let lectures = lectures_by_degree(&client, &degrees);
+
+    let mut out = io::stdout().lock();
+    match cli.output() {
+        Output::Json(mode) => Ok(write_json(&mut out, mode, degree_lectures(&lectures))?),
+        Output::Export(format) => export_lectures(format, &degrees, &lectures, &mut out),
+        Output::Text => Ok(print_lectures_detailed(&mut out, &lectures)?),
+    }
+}
+
+fn search(cli: &Cli, config: &Config, text: &str) -> Result<(), CliError> {
+    let degrees = selected_degrees_or_all(cli, config);
+    let client = client(config, &degrees)?;
+    let hits = client.search(text);
+
+    let mut out = io::stdout().lock();
+    match cli.output() {
+        Output::Json(mode) => Ok(write_json(
+            &mut out,
+            mode,
+            hits.iter().map(|hit| ScoredLecture {
+                score: hit.score,
+                lecture: hit.lecture,
+            }),
+        )?),
+        Output::Export(format) => {
+            let lectures = hits.iter().map(|hit| hit.lecture.clone()).collect::<Vec<_>>();
+            Ok(format.exporter().export(&lectures, &mut out)?)
+        }
+        Output::Text => Ok(print_search_hits(&mut out, &hits)?),
+    }
+}
+
+fn filter(cli: &Cli, config: &Config, args: &FilterArgs) -> Result<(), CliError> {
+    let degrees = selected_degrees_or_all(cli, config);
+    let client = client(config, &degrees)?;
+    let query = Query::new().degrees(degrees.iter().copied()).filter(filter_from_args(args));
+
+    let mut matches = Vec::<(&'static StaticDegree, Vec<Lecture>)>::new();
+    for m in client.query(&query) {
+        match matches.last_mut() {
+            Some((degree, lectures)) if *degree == m.degree => lectures.push(m.lecture.clone()),
+            _ => matches.push((m.degree, vec![m.lecture.clone()])),
+        }
+    }
+    let lectures = matches
+        .iter()
+        .map(|(degree, lectures)| (*degree, lectures.as_slice()))
+        .collect::<Vec<_>>();
+
+    let mut out = io::stdout().lock();
+    match cli.output() {
+        Output::Json(mode) => Ok(write_json(&mut out, mode, degree_lectures(&lectures))?),
+        Output::Export(format) => export_lectures(format, &degrees, &lectures, &mut out),
+        Output::Text => Ok(print_filtered_lectures(&mut out, &lectures)?),
+    }
+}
+
+fn filter_from_args(args: &FilterArgs) -> Filter {
+    let mut filter = Filter::placed_in(&args.module, &args.category);
+    if let Some(title) = &args.title {
+        filter = filter.and(Filter::title(title));
+    }
+    if let Some(lecturer) = &args.lecturer {
+        filter = filter.and(Filter::lecturer(lecturer));
+    }
+    if let Some(ects) = &args.ects {
+        filter = filter.and(Filter::ects(ects.clone()));
+    }
+    if let Some(language) = args.language {
+        filter = filter.and(Filter::language(language));
+    }
+    filter
+}
+
+fn init(cli: &Cli, config: &Config) -> Result<(), CliError> {
+    let degrees = selected_degrees_or_all(cli, config);
+    scrape(cli, config, &degrees)
+}
+
+/// The age after which a cache is outdated, given in hours or otherwise configured
+fn max_age(hours: Option<u64>, config: &Config) -> Result<Duration, CliError> {
+    match hours {
+        Some(hours) => hours
+            .checked_mul(60 * 60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| CliError::usage(format!("A maximum age of {} hours is too large", hours))),
+        None => Ok(config.cache_ttl.unwrap_or(DEFAULT_MAX_AGE)),
+    }
+}
+
+fn refresh(cli: &Cli, config: &Config, max_age: Duration) -> Result<(), CliError> {
+    let stale = stale_degrees(&cache(config), selected_degrees_or_all(cli, config), max_age);
+
+    if stale.is_empty() && cli.json_mode().is_none() {
+        writeln!(io::stdout(), "All cached lectures are up to date.")?;
+        return Ok(());
+    }
+    scrape(cli, config, &stale)
+}
+
+/// The degrees whose lectures were not cached yet or were scraped longer than `max_age` ago
+fn stale_degrees(
+    cache: &FSDataSource,
+    degrees: Vec<&'static StaticDegree>,
+    max_age: Duration,
+) -> Vec<&'static StaticDegree> {
+    degrees
+        .into_iter()
+        .filter(|degree| cache.is_stale(degree, max_age))
+        .collect()
+}
+
+/// Scrapes the lectures of the given degrees and writes them to the cache
+fn scrape(cli: &Cli, config: &Config, degrees: &[&'static StaticDegree]) -> Result<(), CliError> {
+    if config.offline {
+        return Err(CliError::usage("Cannot scrape lectures in offline mode"));
+    }
+
+    let mut client = LectureClient::from_config(config.clone());
+    let mut out = io::stdout().lock();
+    let mut cached = Vec::new();
+    for degree in degrees {
+        let lectures = client.refresh(degree)?.len();
+        if cli.json_mode().is_none() {
+            writeln!(out, "Cached {} lectures of {}", lectures, degree.name)?;
+        }
+        cached.push(CachedDegree {
+            degree: degree.id,
+            lectures,
+        });
+    }
+
+    match cli.json_mode() {
+        Some(mode) => Ok(write_json(&mut out, mode, cached)?),
+        None => Ok(()),
+    }
+}
+
+fn show_cache_status(cli: &Cli, config: &Config) -> Result<(), CliError> {
+    let cache = cache(config);
+    let status = selected_degrees_or_all(cli, config)
+        .into_iter()
+        .map(|degree| Ok((degree, cache.status(degree)?)))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let entries = || status.iter().map(|(degree, status)| CacheEntry::new(degree, status.as_ref()));
+
+    let mut out = io::stdout().lock();
+    match cli.output() {
+        Output::Json(mode) => Ok(write_json(&mut out, mode, entries())?),
+        Output::Export(format) => export_cache_status(&mut out, format, &entries().collect::<Vec<_>>()),
+        Output::Text => Ok(print_cache_status(&mut out, &status)?),
+    }
+}
+
+fn clear_cache(cli: &Cli, config: &Config) -> Result<(), CliError> {
+    let cache = cache(config);
+    let degrees = selected_degrees_or_all(cli, config);
+    for degree in &degrees {
+        cache.clear(degree)?;
+    }
+
+    match cli.json_mode() {
+        Some(mode) => Ok(write_json(
+            &mut io::stdout().lock(),
+            mode,
+            degrees.iter().map(|degree| ClearedDegree { degree: degree.id }),
+        )?),
+        None => Ok(()),
+    }
+}
+
+fn export(cli: &Cli, config: &Config, output: Option<&std::path::Path>) -> Result<(), CliError> {
+    let format = match cli.output() {
+        Output::Export(format) => format,
+        Output::Json(mode) => return export_json(cli, config, mode, output),
+        Output::Text => output
+            .and_then(|path| path.extension())
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+            .unwrap_or(Format::Json),
+    };
+
+    let degrees = selected_degrees(cli)?;
+    let client = client(config, &degrees)?;
+    let lectures = lectures_by_degree(&client, &degrees);
+
+    match output {
+        Some(path) => export_lectures(format, &degrees, &lectures, &mut File::create(path)?),
+        None => export_lectures(format, &degrees, &lectures, &mut io::stdout().lock()),
+    }
+}
+
+/// Exports lectures using their serde representation as requested with `--json` or `--ndjson`
+fn export_json(cli: &Cli, config: &Config, mode: JsonMode, output: Option<&std::path::Path>) -> Result<(), CliError> {
+    let degrees = selected_degrees(cli)?;
+    let client = client(config, &degrees)?;
+    let lectures = unique_lectures(&lectures_by_degree(&client, &degrees));
+
+    match output {
+        Some(path) => Ok(write_json(&mut File::create(path)?, mode, lectures)?),
+        None => Ok(write_json(&mut io::stdout().lock(), mode, lectures)?),
+    }
+}
+
+/// Creates a client and loads the lectures of the given degrees
+fn client(config: &Config, degrees: &[&'static StaticDegree]) -> Result<LectureClient<'static>, CliError> {
+    let mut client = LectureClient::from_config(config.clone());
+    for degree in degrees {
+        client.load_lectures(degree).map_err(|err| {
+            if config.offline {
+                format!("{}. Try to run 'dachterasse init' first.", err)
+            } else {
+                err
+            }
+        })?;
+    }
+    Ok(client)
+}
+
+/// Loads the config file and environment variables and applies the command line arguments on top of them
+pub fn config(cli: &Cli) -> Result<Config, CliError> {
+    let mut config = Config::load(cli.config.as_deref()).map_err(CliError::usage)?;
+    if let Some(cache_dir) = &cli.cache_dir {
+        config.cache_path = Some(cache_dir.clone());
+    }
+    config.cache_path.get_or_insert_with(|| DEFAULT_CACHE_DIR.to_owned());
+    config.offline |= cli.offline;
+    Ok(config)
+}
+
+fn cache(config: &Config) -> FSDataSource {
+    FSDataSource::new(config.cache_path.clone().unwrap_or_else(|| DEFAULT_CACHE_DIR.to_owned()))
+}
+
+fn lectures_by_degree<'c>(
+    client: &'c LectureClient,
+    degrees: &[&'static StaticDegree],
+) -> Vec<(&'static StaticDegree, &'c [Lecture])> {
+    degrees.iter().map(|degree| (*degree, client.lectures(degree))).collect()
+}
+
+/// Exports the lectures of all degrees, listing lectures offered in several degrees only once
+fn export_lectures(
+    format: Format,
+    degrees: &[&StaticDegree],
+    lectures: &[(&'static StaticDegree, &[Lecture])],
+    out: &mut dyn Write,
+) -> Result<(), CliError> {
+    let unique = unique_lectures(lectures).into_iter().cloned().collect::<Vec<_>>();
+    Ok(exporter(format, &export_name(degrees)).export(&unique, out)?)
+}
+
+/// Lists lectures offered in several degrees only once
+fn unique_lectures<'c>(lectures: &[(&'static StaticDegree, &'c [Lecture])]) -> Vec<&'c Lecture> {
+    let mut seen = HashSet::new();
+    lectures
+        .iter()
+        .flat_map(|(_, lectures)| lectures.iter())
+        .filter(|lecture| seen.insert(&lecture.url))
+        .collect()
+}
+
+/// The lectures of all degrees, each together with its degree
+fn degree_lectures<'l, 'c>(
+    lectures: &'l [(&'static StaticDegree, &'c [Lecture])],
+) -> impl Iterator<Item = DegreeLecture<'c>> + 'l {
+    lectures.iter().flat_map(|(degree, lectures)| {
+        lectures.iter().map(|lecture| DegreeLecture {
+            degree: degree.id,
+            lecture,
+        })
+    })
+}
+
+/// Returns the degrees given with `--degree` or all enabled degrees, for commands where that is a sensible default
+fn selected_degrees_or_all(cli: &Cli, config: &Config) -> Vec<&'static StaticDegree> {
+    match &cli.degree {
+        Some(selection) => selection.0.clone(),
+        None => config.enabled_degrees(),
+    }
+}
+
+/// Returns the degrees given with `--degree` or asks for a degree when running in a terminal without JSON output
+fn selected_degrees(cli: &Cli) -> Result<Vec<&'static StaticDegree>, CliError> {
+    match &cli.degree {
+        Some(selection) => Ok(selection.0.clone()),
+        None if io::stdin().is_terminal() && cli.json_mode().is_none() => {
+            Ok(vec![prompt_degree(&mut io::stdin().lock(), &mut io::stdout())?])
+        }
+        None => Err(CliError::usage("No degree selected. Please pass --degree <id|all>")),
+    }
+}
+
+/// Asks for a degree by its index or id until a valid one is entered. Fails if the input ends before.
+fn prompt_degree(input: &mut dyn BufRead, out: &mut dyn Write) -> Result<&'static StaticDegree, CliError> {
+    writeln!(out, "Degrees\n")?;
+
+    let degrees = Degrees::all();
+    for (index, degree) in degrees.iter().enumerate() {
+        writeln!(out, "{} ({})", degree.name, index)?;
+    }
+
+    loop {
+        writeln!(out, "\nPlease choose your degree (0 - {}):", degrees.len() - 1)?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(CliError::usage("No degree selected"));
+        }
+        let input = line.trim();
+        let degree = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| degrees.get(index))
+            .or_else(|| Degrees::by_id(input));
+        match degree {
+            Some(degree) => return Ok(degree),
+            None => writeln!(out, "'{}' is not a valid degree", input)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use dachterasse::datasource::ReadWriteDataSource;
+    use std::time::SystemTime;
+
+    #[test]
+    fn rejects_overflowing_max_age() {
+        let config = Config::new();
+        assert_eq!(max_age(Some(2), &config).unwrap(), Duration::from_secs(2 * 60 * 60));
+        assert_eq!(max_age(Some(u64::MAX), &config).unwrap_err().kind, ErrorKind::Usage);
+    }
+
+    #[test]
+    fn refreshes_degrees_whose_cache_is_outdated_even_after_reading_it() {
+        let dir = std::env::temp_dir().join(format!("dachterasse-refresh-{}", std::process::id()));
+        let cache_dir = dir.to_string_lossy().into_owned();
+        let lectures = [Lecture {
+            title: String::from("Compilerbau"),
+            url: String::from("https://hpi.de/studium/compilerbau.html"),
+            ..Default::default()
+        }];
+        let mut cache = FSDataSource::new(cache_dir.clone());
+        for degree in [&Degrees::ITSE_BA, &Degrees::ITSE_MA] {
+            cache.save_lectures(degree, &lectures).unwrap();
+        }
+        File::options()
+            .write(true)
+            .open(dir.join("itse-ba.json"))
+            .and_then(|file| file.set_modified(SystemTime::now() - Duration::from_secs(2 * 60 * 60)))
+            .unwrap();
+
+        let config = Config::new().cache_path(cache_dir).offline();
+        let degrees = vec![&Degrees::ITSE_BA, &Degrees::ITSE_MA, &Degrees::DE_MA];
+        let client = client(&config, &degrees[..2]).unwrap();
+        assert_eq!(client.lectures(&Degrees::ITSE_BA).len(), 1);
+
+        let stale = stale_degrees(&cache, degrees, Duration::from_secs(60 * 60));
+        assert_eq!(stale, vec![&Degrees::ITSE_BA, &Degrees::DE_MA]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn prompts_until_a_valid_degree_is_entered() {
+        let mut out = Vec::new();
+        let degree = prompt_degree(&mut "7\nitse-ma\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(degree, &Degrees::ITSE_MA);
+        assert!(String::from_utf8(out).unwrap().contains("'7' is not a valid degree"));
+
+        let degree = prompt_degree(&mut "0\n".as_bytes(), &mut io::sink()).unwrap();
+        assert_eq!(degree, &Degrees::all()[0]);
+
+        let error = prompt_degree(&mut "unknown\n".as_bytes(), &mut io::sink()).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Usage);
+        assert_eq!(error.exit_code(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+use serde::Serialize;
+
+/// An error of a CLI command, categorized to determine the exit code
+#[derive(Debug)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+    io_kind: Option<io::ErrorKind>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorKind {
+    /// Lectures could not be loaded, scraped or cached
+    Source,
+    /// Missing or invalid arguments
+    Usage,
+    /// Reading or writing a file or the terminal failed
+    Io,
+}
+
+impl CliError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        CliError {
+            kind,
+            message: message.into(),
+            io_kind: None,
+        }
+    }
+
+    pub fn usage(message: impl Into<String>) -> Self {
+        CliError::new(ErrorKind::Usage, message)
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self.kind {
+            ErrorKind::Source => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::Io => 3,
+        }
+    }
+
+    /// Whether output was piped into a program which stopped reading, e.g. `head`
+    pub fn is_broken_pipe(&self) -> bool {
+        self.io_kind == Some(io::ErrorKind::BrokenPipe)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Errors of the library are plain strings describing why lectures could not be loaded
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::new(ErrorKind::Source, message)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError {
+            kind: ErrorKind::Io,
+            message: error.to_string(),
+            io_kind: Some(error.kind()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(error: serde_json::Error) -> Self {
+        io::Error::from(error).into()
+    }
+}
+
+/// The JSON representation of an error, e.g. `{"error":{"kind":"usage","message":"..."}}`
+#[derive(Serialize)]
+pub struct JsonError<'a> {
+    pub error: JsonErrorDetails<'a>,
+}
+
+#[derive(Serialize)]
+pub struct JsonErrorDetails<'a> {
+    pub kind: ErrorKind,
+    pub message: &'a str,
+    pub command: Option<&'a str>,
+    pub exit_code: i32,
+}
+
+impl<'a> JsonError<'a> {
+    pub fn new(error: &'a CliError, command: Option<&'a str>) -> Self {
+        JsonError {
+            error: JsonErrorDetails {
+                kind: error.kind,
+                message: &error.message,
+                command,
+                exit_code: error.exit_code(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_errors_to_exit_codes() {
+        let source = CliError::from(String::from("No source returned lectures for degree itse-ba"));
+        assert_eq!((source.kind, source.exit_code()), (ErrorKind::Source, 1));
+
+        let usage = CliError::usage("No degree selected");
+        assert_eq!((usage.kind, usage.exit_code()), (ErrorKind::Usage, 2));
+
+        let io = CliError::from(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        assert_eq!((io.kind, io.exit_code()), (ErrorKind::Io, 3));
+        assert!(!io.is_broken_pipe());
+
+        let json = CliError::from(serde_json::from_str::<u8>("x").unwrap_err());
+        assert_eq!(json.exit_code(), 3);
+        assert!(CliError::from(io::Error::from(io::ErrorKind::BrokenPipe)).is_broken_pipe());
+    }
+
+    #[test]
+    fn serializes_errors_as_json() {
+        let error = CliError::usage("No degree selected");
+        let json = serde_json::to_value(JsonError::new(&error, Some("overview"))).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "error": {
+                    "kind": "usage",
+                    "message": "No degree selected",
+                    "command": "overview",
+                    "exit_code": 2,
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use std::env;
+use std::io::{self, Write};
+use std::process;
+
+use clap::Parser;
+
+use args::Cli;
+use error::{CliError, JsonError};
+
+mod args;
+mod commands;
+mod error;
+mod output;
+#[cfg(feature = "tui")]
+mod tui;
+
+fn main() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(error) => {
+            // Help and version are reported as errors by clap but are written to stdout
+            if error.use_stderr() && requested_json() {
+                let error = CliError::usage(error.to_string().trim().trim_start_matches("error: "));
+                print_json_error(&error, None);
+                process::exit(error.exit_code());
+            }
+            error.exit();
+        }
+    };
+
+    if let Err(error) = commands::run(&cli) {
+        if error.is_broken_pipe() {
+            return;
+        }
+        if cli.json_mode().is_some() {
+            print_json_error(&error, Some(cli.command.name()));
+        } else {
+            eprintln!("An error occured while executing 'dachterasse {}': {}", cli.command.name(), error);
+        }
+        process::exit(error.exit_code());
+    }
+}
+
+/// Whether JSON output was requested even though the arguments could not be parsed
+fn requested_json() -> bool {
+    env::args().skip(1).any(|arg| arg == "--json" || arg == "--ndjson")
+}
+
+/// Errors are written to stdout in JSON mode, so that consumers only need to parse one stream
+fn print_json_error(error: &CliError, command: Option<&str>) {
+    let mut out = io::stdout().lock();
+    let _ = serde_json::to_writer(&mut out, &JsonError::new(error, command));
+    let _ = writeln!(out);
+}
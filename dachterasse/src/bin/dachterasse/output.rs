@@ -0,0 +1,360 @@
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dachterasse::export::{write_csv_row, Exporter, Format, HtmlExporter, IcsExporter};
+use dachterasse::search::{Field, SearchHit};
+use dachterasse::sources::CacheStatus;
+use dachterasse::{Lecture, StaticDegree};
+use serde::Serialize;
+
+use crate::args::JsonMode;
+use crate::error::CliError;
+
+/// Escape sequences which start and end bold text on terminals. Output to pipes and files stays plain,
+/// so that scripts do not have to strip them.
+fn bold_codes() -> (&'static str, &'static str) {
+    if io::stdout().is_terminal() {
+        ("\x1b[1m", "\x1b[0m")
+    } else {
+        ("", "")
+    }
+}
+
+pub fn print_lectures(out: &mut dyn Write, lectures: &[(&StaticDegree, &[Lecture])]) -> io::Result<()> {
+    for (degree, degree_lectures) in lectures {
+        print_degree_header(out, degree, lectures.len())?;
+        print_empty_hint(out, degree_lectures)?;
+        for lecture in *degree_lectures {
+            writeln!(out, "{}", lecture.title)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_lectures_detailed(out: &mut dyn Write, lectures: &[(&StaticDegree, &[Lecture])]) -> io::Result<()> {
+    for (degree, degree_lectures) in lectures {
+        print_degree_header(out, degree, lectures.len())?;
+        print_empty_hint(out, degree_lectures)?;
+        for lecture in *degree_lectures {
+            print_lecture_detailed(out, lecture)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_filtered_lectures(out: &mut dyn Write, lectures: &[(&StaticDegree, &[Lecture])]) -> io::Result<()> {
+    if lectures.is_empty() {
+        writeln!(out, "\nNo matching lectures found.")?;
+    }
+
+    for (degree, degree_lectures) in lectures {
+        print_degree_header(out, degree, lectures.len())?;
+        for lecture in *degree_lectures {
+            writeln!(out, "{}", lecture.title)?;
+            writeln!(out, "{}", lecture.url)?;
+            let modules = Field::Modules.text(lecture);
+            if !modules.is_empty() {
+                writeln!(out, "> {}", modules)?;
+            }
+            writeln!(out, "--------------")?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_lecture_detailed(out: &mut dyn Write, lecture: &Lecture) -> io::Result<()> {
+    writeln!(out, "{}", lecture.title)?;
+    writeln!(out, "{}", lecture.url)?;
+    if let Some(c) = &lecture.categories {
+        for (module, categories) in c {
+            writeln!(out, "> {}", module)?;
+            for category in categories {
+                writeln!(out, "\t {}", category)?;
+            }
+        }
+    }
+    writeln!(out, "--------------")
+}
+
+pub fn print_search_hits(out: &mut dyn Write, hits: &[SearchHit]) -> io::Result<()> {
+    if hits.is_empty() {
+        writeln!(out, "\nNo matching lectures found.")?;
+    }
+
+    let (bold, reset) = bold_codes();
+    for hit in hits {
+        let title = hit
+            .highlighted(Field::Title, bold, reset)
+            .unwrap_or_else(|| hit.lecture.title.clone());
+        writeln!(out, "{}", title)?;
+        writeln!(out, "{}", hit.lecture.url)?;
+        if let Some(modules) = hit.highlighted(Field::Modules, bold, reset) {
+            writeln!(out, "> {}", modules)?;
+        }
+        writeln!(out, "--------------")?;
+    }
+    Ok(())
+}
+
+pub fn print_cache_status(out: &mut dyn Write, status: &[(&StaticDegree, Option<CacheStatus>)]) -> io::Result<()> {
+    writeln!(out, "{:<12} {:>8} {:>10} {:>12}", "Degree", "Lectures", "Size", "Age")?;
+    for (degree, status) in status {
+        match status {
+            Some(status) => writeln!(
+                out,
+                "{:<12} {:>8} {:>10} {:>12}",
+                degree.id,
+                status.lectures,
+                format_size(status.size),
+                format_age(status.age())
+            )?,
+            None => writeln!(out, "{:<12} {:>8} {:>10} {:>12}", degree.id, "-", "-", "not cached")?,
+        }
+    }
+    Ok(())
+}
+
+/// Prints the cache status in an export format. Formats which only apply to lectures are rejected.
+pub fn export_cache_status(out: &mut dyn Write, format: Format, entries: &[CacheEntry]) -> Result<(), CliError> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer(&mut *out, entries)?;
+            Ok(writeln!(out)?)
+        }
+        Format::PrettyJson => Ok(write_json(out, JsonMode::Document, entries)?),
+        Format::Csv => {
+            write_csv_row(out, &["degree", "path", "modified", "age_seconds", "size", "lectures"])?;
+            for entry in entries {
+                let optional = |value: Option<String>| value.unwrap_or_default();
+                write_csv_row(
+                    out,
+                    &[
+                        entry.degree,
+                        &optional(entry.path.map(|path| path.display().to_string())),
+                        &optional(entry.modified.map(|modified| modified.to_rfc3339())),
+                        &optional(entry.age_seconds.map(|age| age.to_string())),
+                        &optional(entry.size.map(|size| size.to_string())),
+                        &optional(entry.lectures.map(|lectures| lectures.to_string())),
+                    ],
+                )?;
+            }
+            Ok(())
+        }
+        format => Err(CliError::usage(format!(
+            "The cache status cannot be printed as {}. Use text, csv, json or json-pretty",
+            format
+        ))),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1048575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1048576.0),
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0 => String::from("just now"),
+        1..=59 => format!("{}m", minutes),
+        60..=1439 => format!("{}h {}m", minutes / 60, minutes % 60),
+        _ => format!("{}d {}h", minutes / 1440, minutes % 1440 / 60),
+    }
+}
+
+/// A lecture in the JSON output together with the degree it was found in
+#[derive(Serialize)]
+pub struct DegreeLecture<'a> {
+    pub degree: &'static str,
+    #[serde(flatten)]
+    pub lecture: &'a Lecture,
+}
+
+/// A search result in the JSON output
+#[derive(Serialize)]
+pub struct ScoredLecture<'a> {
+    pub score: f32,
+    #[serde(flatten)]
+    pub lecture: &'a Lecture,
+}
+
+#[derive(Serialize)]
+pub struct CachedDegree {
+    pub degree: &'static str,
+    pub lectures: usize,
+}
+
+#[derive(Serialize)]
+pub struct ClearedDegree {
+    pub degree: &'static str,
+}
+
+/// The cache status of a degree in the JSON output. All fields except the degree are `null` if nothing is cached.
+#[derive(Serialize)]
+pub struct CacheEntry<'a> {
+    pub degree: &'static str,
+    pub path: Option<&'a Path>,
+    pub modified: Option<DateTime<Utc>>,
+    pub age_seconds: Option<u64>,
+    pub size: Option<u64>,
+    pub lectures: Option<usize>,
+}
+
+impl<'a> CacheEntry<'a> {
+    pub fn new(degree: &'static StaticDegree, status: Option<&'a CacheStatus>) -> Self {
+        CacheEntry {
+            degree: degree.id,
+            path: status.map(|status| status.path.as_path()),
+            modified: status.map(|status| status.modified.into()),
+            age_seconds: status.map(|status| status.age().as_secs()),
+            size: status.map(|status| status.size),
+            lectures: status.map(|status| status.lectures),
+        }
+    }
+}
+
+/// Writes the items as a JSON array or as one JSON object per line
+pub fn write_json<T: Serialize>(
+    out: &mut dyn Write,
+    mode: JsonMode,
+    items: impl IntoIterator<Item = T>,
+) -> io::Result<()> {
+    match mode {
+        JsonMode::Document => {
+            serde_json::to_writer_pretty(&mut *out, &items.into_iter().collect::<Vec<_>>())?;
+            writeln!(out)
+        }
+        JsonMode::Lines => {
+            for item in items {
+                serde_json::to_writer(&mut *out, &item)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Creates an exporter for the format, using the given name as title where the format supports one
+pub fn exporter(format: Format, name: &str) -> Box<dyn Exporter> {
+    match format {
+        Format::Ics => Box::new(IcsExporter::new().name(name)),
+        Format::Html => Box::new(HtmlExporter::new().title(name)),
+        format => format.exporter(),
+    }
+}
+
+/// A name for exports of the given degrees
+pub fn export_name(degrees: &[&StaticDegree]) -> String {
+    match degrees {
+        [degree] => degree.name.to_owned(),
+        _ => String::from("HPI lectures"),
+    }
+}
+
+fn print_degree_header(out: &mut dyn Write, degree: &StaticDegree, degree_count: usize) -> io::Result<()> {
+    if degree_count > 1 {
+        let (bold, reset) = bold_codes();
+        writeln!(out, "\n{}{}{}\n", bold, degree.name, reset)?;
+    }
+    Ok(())
+}
+
+fn print_empty_hint(out: &mut dyn Write, lectures: &[Lecture]) -> io::Result<()> {
+    if lectures.is_empty() {
+        writeln!(out, "\nNo lectures found. Try to run 'dachterasse init'.")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dachterasse::Degrees;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn lecture(title: &str) -> Lecture {
+        Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/studium/{}.html", title.to_lowercase()),
+            ects: Some(6),
+            ..Default::default()
+        }
+    }
+
+    fn render(print: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        print(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn prints_cache_status_table() {
+        let status = CacheStatus {
+            path: PathBuf::from("cache/itse-ba.json"),
+            modified: SystemTime::now() - Duration::from_secs(90 * 60),
+            size: 2048,
+            lectures: 42,
+        };
+        let table = render(|out| {
+            print_cache_status(out, &[(&Degrees::ITSE_BA, Some(status)), (&Degrees::ITSE_MA, None)])
+        });
+
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Degree       Lectures       Size          Age");
+        assert_eq!(lines[1], "itse-ba            42    2.0 KiB       1h 30m");
+        assert_eq!(lines[2], "itse-ma             -          -   not cached");
+    }
+
+    #[test]
+    fn exports_cache_status() {
+        let entries = [CacheEntry::new(&Degrees::ITSE_MA, None)];
+
+        let csv = render(|out| export_cache_status(out, Format::Csv, &entries).map_err(io::Error::other));
+        assert_eq!(csv, "degree,path,modified,age_seconds,size,lectures\r\nitse-ma,,,,,\r\n");
+
+        let json = render(|out| export_cache_status(out, Format::Json, &entries).map_err(io::Error::other));
+        let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(parsed[0]["degree"], "itse-ma");
+        assert!(parsed[0]["lectures"].is_null());
+        assert_eq!(json.lines().count(), 1);
+
+        assert!(export_cache_status(&mut io::sink(), Format::Ics, &entries).is_err());
+    }
+
+    #[test]
+    fn writes_json_documents_and_lines() {
+        let lectures = [lecture("Compilerbau"), lecture("Datenbanksysteme")];
+        let items = || {
+            lectures.iter().map(|lecture| DegreeLecture {
+                degree: Degrees::ITSE_BA.id,
+                lecture,
+            })
+        };
+
+        let document = render(|out| write_json(out, JsonMode::Document, items()));
+        let parsed = serde_json::from_str::<serde_json::Value>(&document).unwrap();
+        assert_eq!(parsed[1]["degree"], "itse-ba");
+        assert_eq!(parsed[1]["title"], "Datenbanksysteme");
+
+        let lines = render(|out| write_json(out, JsonMode::Lines, items()));
+        assert_eq!(lines.lines().count(), 2);
+        assert!(lines.lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = render(|out| exporter(Format::Csv, "HPI lectures").export(&[lecture("Compilerbau")], out));
+
+        assert_eq!(
+            csv,
+            "id,title,url,ects,language,semester,lecturers,module,category\r\n\
+             compilerbau,Compilerbau,https://hpi.de/studium/compilerbau.html,6,,,,,\r\n"
+        );
+    }
+}
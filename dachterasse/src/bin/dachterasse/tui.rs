@@ -0,0 +1,435 @@
+//! Interactive terminal UI for browsing the lectures of a degree and adding them to a study plan
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use dachterasse::plan::StudyPlan;
+use dachterasse::search::Field;
+use dachterasse::{Config, Language, Lecture, LectureClient, StaticDegree};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::args::Cli;
+use crate::error::CliError;
+
+const MAX_SEMESTER: u8 = 12;
+
+pub fn run(cli: &Cli, config: &Config, plan_dir: &Path) -> Result<(), CliError> {
+    if cli.json_mode().is_some() {
+        return Err(CliError::usage("The terminal UI does not support JSON output"));
+    }
+
+    let mut app = App::new(LectureClient::from_config(config.clone()), plan_dir.to_path_buf());
+    if let Some(selection) = &cli.degree {
+        if let [degree] = selection.0.as_slice() {
+            app.open_degree(degree);
+        }
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+enum Screen {
+    Degrees,
+    Lectures,
+}
+
+#[derive(PartialEq, Eq)]
+enum Mode {
+    Normal,
+    /// Typed characters are appended to the filter
+    Filtering,
+}
+
+struct App<'a> {
+    client: LectureClient<'a>,
+    plan_dir: PathBuf,
+    screen: Screen,
+    mode: Mode,
+    degrees: ListState,
+    degree: Option<&'static StaticDegree>,
+    filter: String,
+    /// Indices of the lectures of the current degree which match the filter
+    visible: Vec<usize>,
+    lectures: ListState,
+    plan: Option<StudyPlan>,
+    semester: u8,
+    status: String,
+    quit: bool,
+}
+
+impl<'a> App<'a> {
+    fn new(client: LectureClient<'a>, plan_dir: PathBuf) -> Self {
+        App {
+            client,
+            plan_dir,
+            screen: Screen::Degrees,
+            mode: Mode::Normal,
+            degrees: ListState::default().with_selected(Some(0)),
+            degree: None,
+            filter: String::new(),
+            visible: Vec::new(),
+            lectures: ListState::default(),
+            plan: None,
+            semester: 1,
+            status: String::new(),
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<(), CliError> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match (&self.screen, &self.mode) {
+            (Screen::Degrees, _) => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+                KeyCode::Down | KeyCode::Char('j') => self.degrees.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.degrees.select_previous(),
+                KeyCode::Enter => {
+                    let degrees = self.client.degrees();
+                    if let Some(degree) = degrees.get(self.degrees.selected().unwrap_or_default()).copied() {
+                        self.open_degree(degree);
+                    }
+                }
+                _ => {}
+            },
+            (Screen::Lectures, Mode::Filtering) => match key.code {
+                KeyCode::Esc | KeyCode::Enter => self.mode = Mode::Normal,
+                KeyCode::Down => self.lectures.select_next(),
+                KeyCode::Up => self.lectures.select_previous(),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            },
+            (Screen::Lectures, Mode::Normal) => match key.code {
+                KeyCode::Char('q') => self.quit = true,
+                KeyCode::Esc | KeyCode::Backspace => self.screen = Screen::Degrees,
+                KeyCode::Down | KeyCode::Char('j') => self.lectures.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.lectures.select_previous(),
+                KeyCode::Char('/') => self.mode = Mode::Filtering,
+                KeyCode::Char('o') => self.open_selected(),
+                KeyCode::Char('a') => self.add_selected(),
+                KeyCode::Char('d') => self.remove_selected(),
+                KeyCode::Char(']') => self.semester = (self.semester + 1).min(MAX_SEMESTER),
+                KeyCode::Char('[') => self.semester = (self.semester - 1).max(1),
+                _ => {}
+            },
+        }
+    }
+
+    fn open_degree(&mut self, degree: &'static StaticDegree) {
+        if let Err(err) = self.client.load_lectures(degree) {
+            self.status = err;
+            return;
+        }
+
+        self.plan = match load_plan(&self.plan_path(degree)) {
+            Ok(plan) => plan.filter(|plan| plan.degree == degree.id),
+            Err(err) => {
+                self.status = format!("Could not load study plan: {}", err);
+                None
+            }
+        };
+        self.degree = Some(degree);
+        self.screen = Screen::Lectures;
+        self.filter.clear();
+        self.apply_filter();
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.visible = self
+            .current_lectures()
+            .iter()
+            .enumerate()
+            .filter(|(_, lecture)| {
+                filter.is_empty()
+                    || lecture.title.to_lowercase().contains(&filter)
+                    || Field::Modules.text(lecture).to_lowercase().contains(&filter)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.lectures
+            .select(if self.visible.is_empty() { None } else { Some(0) });
+    }
+
+    fn current_lectures(&self) -> &[Lecture] {
+        self.degree.map_or(&[], |degree| self.client.lectures(degree))
+    }
+
+    fn selected_lecture(&self) -> Option<&Lecture> {
+        let index = *self.visible.get(self.lectures.selected()?)?;
+        self.current_lectures().get(index)
+    }
+
+    fn open_selected(&mut self) {
+        if let Some(lecture) = self.selected_lecture() {
+            self.status = match open_url(&lecture.url) {
+                Ok(()) => format!("Opened {}", lecture.url),
+                Err(err) => format!("Could not open {}: {}", lecture.url, err),
+            };
+        }
+    }
+
+    fn add_selected(&mut self) {
+        let (Some(degree), Some(lecture)) = (self.degree, self.selected_lecture().cloned()) else {
+            return;
+        };
+        let plan = self.plan.get_or_insert_with(|| StudyPlan::new(degree));
+        if plan.lectures().any(|(_, planned)| planned.lecture.url == lecture.url) {
+            self.status = format!("{} is already planned", lecture.title);
+            return;
+        }
+
+        plan.add(self.semester, lecture.clone());
+        self.status = format!("Added {} to semester {}", lecture.title, self.semester);
+        self.save_plan();
+    }
+
+    fn remove_selected(&mut self) {
+        let Some(url) = self.selected_lecture().map(|lecture| lecture.url.clone()) else {
+            return;
+        };
+        let Some(plan) = &mut self.plan else {
+            return;
+        };
+        let semesters = plan.lectures().map(|(semester, _)| semester).collect::<Vec<_>>();
+        if semesters.into_iter().any(|semester| plan.remove(semester, &url)) {
+            self.status = String::from("Removed lecture from plan");
+            self.save_plan();
+        }
+    }
+
+    fn save_plan(&mut self) {
+        let (Some(degree), Some(plan)) = (self.degree, &self.plan) else {
+            return;
+        };
+        if let Err(err) = save_plan(&self.plan_path(degree), plan) {
+            self.status = format!("Could not save study plan: {}", err);
+        }
+    }
+
+    fn plan_path(&self, degree: &StaticDegree) -> PathBuf {
+        self.plan_dir.join(format!("{}.json", degree.id))
+    }
+
+    fn planned_semester(&self, lecture: &Lecture) -> Option<u8> {
+        self.plan
+            .as_ref()?
+            .lectures()
+            .find(|(_, planned)| planned.lecture.url == lecture.url)
+            .map(|(semester, _)| semester)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        match self.screen {
+            Screen::Degrees => self.draw_degrees(frame),
+            Screen::Lectures => self.draw_lectures(frame),
+        }
+    }
+
+    fn draw_degrees(&mut self, frame: &mut Frame) {
+        let [list_area, help_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        let items = self
+            .client
+            .degrees()
+            .iter()
+            .map(|degree| ListItem::new(degree.name))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Degrees "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.degrees);
+
+        let help = if self.status.is_empty() {
+            String::from("↑/↓ select · Enter open · q quit")
+        } else {
+            self.status.clone()
+        };
+        frame.render_widget(Paragraph::new(help), help_area);
+    }
+
+    fn draw_lectures(&mut self, frame: &mut Frame) {
+        let [filter_area, main_area, status_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main_area);
+
+        let filter_title = if self.mode == Mode::Filtering {
+            " Filter (Enter to finish) "
+        } else {
+            " Filter (/) "
+        };
+        frame.render_widget(
+            Paragraph::new(self.filter.as_str()).block(Block::default().borders(Borders::ALL).title(filter_title)),
+            filter_area,
+        );
+
+        let lectures = self.current_lectures();
+        let items = self
+            .visible
+            .iter()
+            .map(|&index| {
+                let lecture = &lectures[index];
+                match self.planned_semester(lecture) {
+                    Some(semester) => ListItem::new(format!("[{}] {}", semester, lecture.title)),
+                    None => ListItem::new(format!("    {}", lecture.title)),
+                }
+            })
+            .collect::<Vec<_>>();
+        let title = format!(
+            " {} ({}/{}) ",
+            self.degree.map_or("", |degree| degree.name),
+            self.visible.len(),
+            lectures.len()
+        );
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.lectures);
+
+        let details = self.selected_lecture().map(lecture_details).unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" Details ")),
+            detail_area,
+        );
+
+        let planned_ects = self
+            .plan
+            .as_ref()
+            .map_or(0, |plan| plan.lectures().filter_map(|(_, p)| p.lecture.ects).map(u16::from).sum::<u16>());
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Semester {} · {} ECTS planned · {}",
+                self.semester, planned_ects, self.status
+            )),
+            status_area,
+        );
+        frame.render_widget(
+            Paragraph::new("↑/↓ select · / filter · o open · a add to plan · d remove · [/] semester · Esc back · q quit"),
+            help_area,
+        );
+    }
+}
+
+fn lecture_details(lecture: &Lecture) -> Text<'static> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(Span::styled(lecture.title.clone(), bold)),
+        Line::from(lecture.url.clone()),
+        Line::default(),
+    ];
+
+    let mut field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            lines.push(Line::from(vec![Span::styled(format!("{}: ", name), bold), Span::raw(value)]));
+        }
+    };
+    field("ECTS", lecture.ects.map(|ects| ects.to_string()));
+    field(
+        "Language",
+        lecture.language.map(|language| match language {
+            Language::German => String::from("German"),
+            Language::English => String::from("English"),
+        }),
+    );
+    field("Semester", lecture.semester.clone());
+    field(
+        "Lecturers",
+        Some(lecture.lecturers.join(", ")).filter(|lecturers| !lecturers.is_empty()),
+    );
+
+    if let Some(categories) = &lecture.categories {
+        let mut modules = categories.iter().collect::<Vec<_>>();
+        modules.sort();
+        lines.push(Line::default());
+        for (module, categories) in modules {
+            lines.push(Line::from(Span::styled(module.clone(), bold)));
+            for category in categories {
+                lines.push(Line::from(format!("  {}", category)));
+            }
+        }
+    }
+
+    if let Some(description) = &lecture.description {
+        lines.push(Line::default());
+        lines.extend(description.lines().map(|line| Line::from(line.to_owned())));
+    }
+
+    Text::from(lines)
+}
+
+fn load_plan(path: &Path) -> io::Result<Option<StudyPlan>> {
+    match File::open(path) {
+        Ok(file) => Ok(Some(serde_json::from_reader(file)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn save_plan(path: &Path, plan: &StudyPlan) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    serde_json::to_writer_pretty(File::create(path)?, plan)?;
+    Ok(())
+}
+
+/// Opens a URL in the default browser
+fn open_url(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
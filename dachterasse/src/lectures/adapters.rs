@@ -0,0 +1,176 @@
+use std::sync::{Arc, RwLock};
+
+use async_std::task;
+use async_trait::async_trait;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::asynch::datasource as asynchronous;
+use crate::datasource as synchronous;
+use crate::{Lecture, StaticDegree};
+
+/// Wraps an async data source so that it can be used wherever a sync data source is expected,
+/// e.g. in a sync `LectureRepository` or the `LectureClient`.
+///
+/// Every call blocks the current thread until the wrapped future completes. Futures run on a tokio
+/// runtime, since sources like the async scraper or database sources depend on its reactor.
+/// Calls must therefore not be made from within an async task of that runtime.
+pub struct BlockingSource<S> {
+    source: S,
+    handle: Handle,
+    /// The runtime created for this source, if no handle to an existing one was given
+    _runtime: Option<Runtime>,
+}
+
+impl<S> BlockingSource<S> {
+    /// Wraps the source, running its futures on a runtime owned by the adapter
+    pub fn new(source: S) -> Self {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("Could not initialize async runtime");
+
+        BlockingSource {
+            source,
+            handle: runtime.handle().clone(),
+            _runtime: Some(runtime),
+        }
+    }
+
+    /// Wraps the source, running its futures on an existing multi-threaded runtime
+    pub fn with_handle(source: S, handle: Handle) -> Self {
+        BlockingSource {
+            source,
+            handle,
+            _runtime: None,
+        }
+    }
+
+    /// Returns the wrapped async data source
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: asynchronous::ReadOnlyDataSource> synchronous::ReadOnlyDataSource for BlockingSource<S> {
+    fn load_lectures(&self, degree: &'static StaticDegree) -> synchronous::LoadResult {
+        self.handle.block_on(self.source.load_lectures(degree))
+    }
+}
+
+impl<S: asynchronous::ReadWriteDataSource> synchronous::ReadWriteDataSource for BlockingSource<S> {
+    fn save_lectures(&mut self, degree: &'static StaticDegree, lectures: &[Lecture]) -> synchronous::SaveResult {
+        self.handle.block_on(self.source.save_lectures(degree, lectures))
+    }
+
+    fn clear_lectures(&mut self, degree: &'static StaticDegree) -> synchronous::SaveResult {
+        self.handle.block_on(self.source.clear_lectures(degree))
+    }
+}
+
+/// Wraps a sync data source so that it can be used wherever an async data source is expected,
+/// e.g. in an async `LectureRepository`.
+///
+/// Calls to the wrapped source are moved to a thread pool for blocking operations,
+/// so that blocking I/O like the sync scraper does not stall the async executor.
+/// Writes take a lock on the wrapped source as sync sources require exclusive access for saving.
+pub struct AsyncSource<S> {
+    source: Arc<RwLock<S>>,
+}
+
+impl<S> AsyncSource<S> {
+    pub fn new(source: S) -> Self {
+        AsyncSource {
+            source: Arc::new(RwLock::new(source)),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: synchronous::ReadOnlyDataSource + 'static> asynchronous::ReadOnlyDataSource for AsyncSource<S> {
+    async fn load_lectures(&self, degree: &'static StaticDegree) -> asynchronous::LoadResult {
+        let source = Arc::clone(&self.source);
+        task::spawn_blocking(move || {
+            source
+                .read()
+                .map_err(|err| err.to_string())?
+                .load_lectures(degree)
+        })
+        .await
+    }
+
+    fn name(&self) -> &'static str {
+        asynchronous::short_type_name::<S>()
+    }
+}
+
+#[async_trait]
+impl<S: synchronous::ReadWriteDataSource + 'static> asynchronous::ReadWriteDataSource for AsyncSource<S> {
+    async fn save_lectures(&self, degree: &'static StaticDegree, lectures: &[Lecture]) -> asynchronous::SaveResult {
+        let source = Arc::clone(&self.source);
+        let lectures = Vec::from(lectures);
+        task::spawn_blocking(move || {
+            source
+                .write()
+                .map_err(|err| err.to_string())?
+                .save_lectures(degree, &lectures)
+        })
+        .await
+    }
+
+    async fn clear_lectures(&self, degree: &'static StaticDegree) -> asynchronous::SaveResult {
+        let source = Arc::clone(&self.source);
+        task::spawn_blocking(move || {
+            source
+                .write()
+                .map_err(|err| err.to_string())?
+                .clear_lectures(degree)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::asynch::sources::InMemoryDataSource;
+    use crate::datasource::{ReadOnlyDataSource, ReadWriteDataSource};
+    use crate::Degrees;
+
+    /// A source whose futures need a tokio reactor, like sources based on reqwest or sqlx
+    struct TimerSource(InMemoryDataSource);
+
+    #[async_trait]
+    impl asynchronous::ReadOnlyDataSource for TimerSource {
+        async fn load_lectures(&self, degree: &'static StaticDegree) -> asynchronous::LoadResult {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            self.0.load_lectures(degree).await
+        }
+    }
+
+    #[async_trait]
+    impl asynchronous::ReadWriteDataSource for TimerSource {
+        async fn save_lectures(&self, degree: &'static StaticDegree, lectures: &[Lecture]) -> asynchronous::SaveResult {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            self.0.save_lectures(degree, lectures).await
+        }
+    }
+
+    #[test]
+    fn runs_sources_depending_on_tokio() {
+        let lectures = vec![Lecture {
+            title: String::from("Datenbanksysteme I"),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            ..Default::default()
+        }];
+        let mut source = BlockingSource::new(TimerSource(InMemoryDataSource::new()));
+        source.save_lectures(&Degrees::ITSE_BA, &lectures).unwrap();
+        assert_eq!(source.load_lectures(&Degrees::ITSE_BA).unwrap().len(), 1);
+
+        let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
+        let source = BlockingSource::with_handle(TimerSource(source.into_inner().0), runtime.handle().clone());
+        assert_eq!(source.load_lectures(&Degrees::ITSE_BA).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::entities::{Degrees, Lecture, StaticDegree};
+
+/// The modules and categories in which a lecture can be credited within one degree
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Placement {
+    pub degree: &'static StaticDegree,
+    pub modules: HashMap<String, Vec<String>>,
+}
+
+/// A lecture as listed across all degrees
+#[derive(Serialize, Clone, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CatalogueEntry {
+    pub id: String,
+    /// Lecture information shared by all degrees. Module placements are listed in `placements` instead.
+    pub lecture: Lecture,
+    pub placements: Vec<Placement>,
+}
+
+impl CatalogueEntry {
+    /// All degrees in which this lecture is offered
+    pub fn degrees(&self) -> impl Iterator<Item = &'static StaticDegree> + '_ {
+        self.placements.iter().map(|placement| placement.degree)
+    }
+
+    pub fn placement(&self, degree: &'static StaticDegree) -> Option<&Placement> {
+        self.placements.iter().find(|placement| placement.degree == degree)
+    }
+}
+
+/// All lectures across degrees, each listed only once together with its module placements for every degree
+#[derive(Default)]
+pub struct Catalogue {
+    entries: Vec<CatalogueEntry>,
+    index: HashMap<String, usize>,
+}
+
+impl Catalogue {
+    pub fn new() -> Self {
+        Catalogue::default()
+    }
+
+    /// Builds a catalogue from lectures grouped by degree, visiting degrees in the order of `Degrees::all()`
+    pub fn from_lectures(lectures: &HashMap<&'static StaticDegree, Vec<Lecture>>) -> Self {
+        let mut catalogue = Catalogue::new();
+        for degree in Degrees::all() {
+            for lecture in lectures.get(degree).into_iter().flatten() {
+                catalogue.add(degree, lecture);
+            }
+        }
+        catalogue
+    }
+
+    /// Adds a lecture as found in the given degree. Lectures with the same URL are merged into one entry.
+    pub fn add(&mut self, degree: &'static StaticDegree, lecture: &Lecture) {
+        let placement = Placement {
+            degree,
+            modules: lecture.categories.clone().unwrap_or_default(),
+        };
+
+        match self.index.get(&lecture.url) {
+            Some(&position) => {
+                let entry = &mut self.entries[position];
+                merge(&mut entry.lecture, lecture);
+                match entry.placements.iter_mut().find(|p| p.degree == degree) {
+                    Some(existing) => existing.modules.extend(placement.modules),
+                    None => entry.placements.push(placement),
+                }
+            }
+            None => {
+                let mut shared = lecture.clone();
+                shared.categories = None;
+                self.index.insert(lecture.url.clone(), self.entries.len());
+                self.entries.push(CatalogueEntry {
+                    id: lecture.id().to_owned(),
+                    lecture: shared,
+                    placements: vec![placement],
+                });
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[CatalogueEntry] {
+        &self.entries
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CatalogueEntry> {
+        self.entries.iter()
+    }
+
+    /// Finds a lecture by its id as returned by `Lecture::id`
+    pub fn get(&self, id: &str) -> Option<&CatalogueEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn get_by_url(&self, url: &str) -> Option<&CatalogueEntry> {
+        self.index.get(url).map(|&position| &self.entries[position])
+    }
+
+    /// All lectures offered in the given degree
+    pub fn for_degree(&self, degree: &'static StaticDegree) -> impl Iterator<Item = &CatalogueEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.placement(degree).is_some())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A module of a degree together with the categories in which its lectures can be credited
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Module {
+    pub name: String,
+    pub categories: Vec<Category>,
+    /// Number of lectures which can be credited in this module
+    pub lectures: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Category {
+    pub name: String,
+    /// Number of lectures which can be credited in this category
+    pub lectures: usize,
+}
+
+/// Derives the modules and categories of a degree from the placements of its lectures, both sorted by name
+pub fn module_tree(lectures: &[Lecture]) -> Vec<Module> {
+    let mut modules: BTreeMap<&str, (usize, BTreeMap<&str, usize>)> = BTreeMap::new();
+    for (module, categories) in lectures.iter().flat_map(|lecture| lecture.categories.iter().flatten()) {
+        let (count, module_categories) = modules.entry(module).or_default();
+        *count += 1;
+        for category in categories {
+            *module_categories.entry(category).or_default() += 1;
+        }
+    }
+
+    modules
+        .into_iter()
+        .map(|(name, (lectures, categories))| Module {
+            name: name.to_owned(),
+            categories: categories
+                .into_iter()
+                .map(|(name, lectures)| Category {
+                    name: name.to_owned(),
+                    lectures,
+                })
+                .collect(),
+            lectures,
+        })
+        .collect()
+}
+
+/// Fills in information which is missing in the catalogue entry but known from another degree's page
+fn merge(shared: &mut Lecture, other: &Lecture) {
+    if shared.description.is_none() {
+        shared.description = other.description.clone();
+    }
+    if shared.lecturers.is_empty() {
+        shared.lecturers = other.lecturers.clone();
+    }
+    if shared.ects.is_none() {
+        shared.ects = other.ects;
+    }
+    if shared.language.is_none() {
+        shared.language = other.language;
+    }
+    if shared.semester.is_none() {
+        shared.semester = other.semester.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_lectures_across_degrees() {
+        let lecture = |module: &str| Lecture {
+            title: String::from("Datenbanksysteme II"),
+            url: String::from("https://hpi.de/studium/lehrveranstaltungen/dbs2.html"),
+            categories: Some(HashMap::from([(module.to_owned(), vec![])])),
+            ..Default::default()
+        };
+        let lectures = HashMap::from([
+            (&Degrees::ITSE_BA, vec![lecture("Vertiefungsgebiete")]),
+            (&Degrees::DE_MA, vec![lecture("Data Management")]),
+        ]);
+
+        let catalogue = Catalogue::from_lectures(&lectures);
+        let entry = catalogue.get("dbs2").unwrap();
+
+        assert_eq!(catalogue.len(), 1);
+        assert_eq!(entry.degrees().map(|d| d.id).collect::<Vec<_>>(), vec!["itse-ba", "de-ma"]);
+        assert!(entry.placement(&Degrees::DE_MA).unwrap().modules.contains_key("Data Management"));
+    }
+}
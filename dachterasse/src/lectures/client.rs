@@ -1,38 +1,60 @@
 use super::config::Config;
 use super::entities::Lecture;
+use super::loaded::LoadedLectures;
 use crate::datasource::Error;
 use crate::lectures::entities::StaticDegree;
 use crate::repository::LectureRepository;
 use crate::sources::*;
 use crate::Degrees;
-use std::collections::HashMap;
+use std::ops::Deref;
 
+/// Loads lectures from a repository of sync data sources. Loaded lectures are queried through
+/// [`LoadedLectures`], which the client dereferences to.
 pub struct LectureClient<'a> {
     repository: LectureRepository<'a>,
-    lectures: HashMap<&'static StaticDegree, Vec<Lecture>>,
+    lectures: LoadedLectures,
+    degrees: Vec<&'static StaticDegree>,
 }
 
 impl<'a> LectureClient<'a> {
     pub fn from_config(config: Config) -> Self {
         let mut repository = LectureRepository::new();
         if let Some(path) = config.get_cache_path() {
-            repository.add_source(FSDataSource::new(path.to_string()));
-            repository.add_readonly_source(ScraperSource::new());
+            let mut cache = FSDataSource::new(path.to_string());
+            // Outdated lectures are still better than none when scraping is disabled
+            if let (Some(ttl), false) = (config.cache_ttl, config.offline) {
+                cache = cache.ttl(ttl);
+            }
+            repository.add_source(cache);
+        }
+        if !config.offline {
+            repository.add_readonly_source(ScraperSource::from_config(&config));
         }
 
-        let mut lectures = HashMap::new();
-        for degree in Degrees::all() {
-            lectures.insert(degree, Vec::new());
+        LectureClient {
+            degrees: config.enabled_degrees(),
+            ..Self::from_repository(repository)
         }
+    }
+
+    /// Creates a client which serves lectures from a custom repository.
+    /// Async data sources can be added to the repository by wrapping them in an `adapters::BlockingSource`.
+    pub fn from_repository(repository: LectureRepository<'a>) -> Self {
         LectureClient {
             repository,
-            lectures,
+            lectures: LoadedLectures::default(),
+            degrees: Degrees::all().iter().collect(),
         }
     }
 
+    /// The degrees which are loaded by `init`
+    pub fn degrees(&self) -> &[&'static StaticDegree] {
+        &self.degrees
+    }
+
     /// Call after creating LectureClient to ensure lectures were loaded
     pub fn init(&mut self) -> Result<(), Error> {
-        for degree in Degrees::all() {
+        for degree in self.degrees.clone() {
             self.load_lectures(degree)?;
         }
         Ok(())
@@ -43,39 +65,48 @@ impl<'a> LectureClient<'a> {
         self
     }
 
-    /// Returns lectures if the client was already initialized or an empty slice otherwise
-    pub fn lectures(&self, degree: &'static StaticDegree) -> &[Lecture] {
-        &self.lectures[degree]
+    /// Loads the lectures of a single degree, e.g. to initialize only the degrees that are needed
+    pub fn load_lectures(&mut self, degree: &'static StaticDegree) -> Result<&[Lecture], Error> {
+        let lectures = self.repository.synchronized_load(degree)?;
+        Ok(self.lectures.insert(degree, lectures))
     }
 
-    fn load_lectures(&mut self, degree: &'static StaticDegree) -> Result<&[Lecture], Error> {
-        self.lectures
-            .insert(degree, self.repository.synchronized_load(degree)?);
-        Ok(&self.lectures[degree])
+    /// Loads the lectures of a degree from the read-only sources, e.g. by scraping them, and updates all caches
+    pub fn refresh(&mut self, degree: &'static StaticDegree) -> Result<&[Lecture], Error> {
+        let lectures = self.repository.refresh(degree)?;
+        Ok(self.lectures.insert(degree, lectures))
     }
+}
+
+impl Deref for LectureClient<'_> {
+    type Target = LoadedLectures;
+
+    fn deref(&self) -> &LoadedLectures {
+        &self.lectures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::ReadWriteDataSource;
+
+    #[test]
+    fn queries_loaded_lectures() {
+        let lecture = Lecture {
+            title: String::from("Datenbanksysteme I"),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            ..Default::default()
+        };
+        let mut source = InMemoryDataSource::new();
+        source.save_lectures(&Degrees::ITSE_BA, &[lecture]).unwrap();
+        let mut client = LectureClient::from_repository(LectureRepository::new().source(source));
 
-    /// Returns all lectures that match the given search criteria
-    ///
-    /// # Arguments
-    ///
-    /// * `modules` - Search for all lectures matching any of the given module names literally
-    pub fn filter_lectures(
-        &mut self,
-        modules: Vec<&str>,
-        degree: &'static StaticDegree,
-    ) -> Vec<&Lecture> {
-        // TODO: Error Handling
-        self.lectures[degree]
-            .iter()
-            .filter(|lecture| {
-                modules.iter().any(|&module| {
-                    if let Some(c) = &lecture.categories {
-                        c.contains_key(module)
-                    } else {
-                        false
-                    }
-                })
-            })
-            .collect()
+        assert!(client.lecture("dbs1").is_none());
+        assert!(client.init().is_err());
+        assert_eq!(client.load_lectures(&Degrees::ITSE_BA).unwrap().len(), 1);
+        assert_eq!(client.lecture("dbs1").unwrap().title, "Datenbanksysteme I");
+        assert_eq!(client.search("datenbanksysteme").len(), 1);
+        assert!(client.timetable(&["dbs1", "dbs2"]).is_err());
     }
 }
@@ -1,13 +1,95 @@
-#[derive(Default)]
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::entities::{Degrees, StaticDegree};
+use super::parse::BASE_URL;
+
+pub type Error = String;
+
+/// Prefix of all environment variables read by [`Config::load`]
+pub const ENV_PREFIX: &str = "DACHTERASSE_";
+
+/// Configuration of a `LectureClient` or the server.
+///
+/// A config can be built in code or loaded with [`Config::load`] from a TOML file and `DACHTERASSE_*`
+/// environment variables:
+///
+/// ```toml
+/// cache_dir = "/var/cache/dachterasse"
+/// cache_ttl = "1day"
+/// degrees = ["itse-ba", "itse-ma"]
+/// concurrency = 8
+/// timeout = "30s"
+/// rate_limit = 2.0
+/// contact = "mailto:lectures@example.org"
+/// ```
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     /// Path to store lecture cache as JSON file. If set to None, no cache is created.
+    #[serde(rename = "cache_dir")]
     pub cache_path: Option<String>,
+    /// If set, lectures are only loaded from the cache and never scraped
+    pub offline: bool,
+    /// Time after which cached lectures are scraped again. If set to None, the cache never expires.
+    #[serde(with = "humantime_serde")]
+    pub cache_ttl: Option<Duration>,
+    /// Ids of the degrees to load lectures for. If set to None, all degrees are loaded.
+    pub degrees: Option<Vec<String>>,
+    /// Maximum number of lecture pages which are scraped at the same time
+    pub concurrency: usize,
+    /// Timeout of a single request to the lecture overview site
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    pub user_agent: String,
+    /// Contact information such as an email address or URL, sent along with the user agent so that
+    /// the site's operators can reach out about the scraper
+    pub contact: Option<String>,
+    /// Maximum number of requests per second to the lecture site, shared by all concurrent fetches.
+    /// If set to 0, requests are not limited.
+    pub rate_limit: f64,
+    /// If set, pages which the site's robots.txt disallows are not scraped
+    pub respect_robots_txt: bool,
+    /// URL of the database in which the server stores lectures, e.g. `sqlite://lectures.db`.
+    /// If set to None, the server does not use a database.
+    pub database_url: Option<String>,
+    /// Bearer token required by the server's admin endpoints. If set to None, the admin endpoints are disabled.
+    pub admin_token: Option<String>,
+    /// If set, the server serves an interactive documentation of its API at `/docs`
+    pub api_docs: bool,
+    /// URL of the site to scrape, e.g. to use a mirror. Lecture and degree URLs are resolved relative to it.
+    pub base_url: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cache_path: None,
+            offline: false,
+            cache_ttl: None,
+            degrees: None,
+            concurrency: 4,
+            timeout: Duration::from_secs(30),
+            user_agent: format!("dachterasse/{}", env!("CARGO_PKG_VERSION")),
+            contact: None,
+            rate_limit: 2.0,
+            respect_robots_txt: true,
+            database_url: None,
+            admin_token: None,
+            api_docs: false,
+            base_url: BASE_URL.to_owned(),
+        }
+    }
 }
 
 impl Config {
     /// Creates a new config without a cache path configured
     pub fn new() -> Self {
-        Config { cache_path: None }
+        Config::default()
     }
 
     /// Create a config with the default cache path `/cache/<degree_id>.json`
@@ -15,13 +97,226 @@ impl Config {
         Config::new().cache_path("cache".to_string())
     }
 
+    /// Loads the config from the following sources, where later sources take precedence over earlier ones:
+    ///
+    /// 1. Default values
+    /// 2. The TOML file at `path` or, if no path is given, `<config dir>/dachterasse/config.toml` if it exists
+    /// 3. `DACHTERASSE_*` environment variables, e.g. `DACHTERASSE_CACHE_DIR`
+    pub fn load(path: Option<&Path>) -> Result<Config, Error> {
+        let config = match path {
+            Some(path) => Config::from_file(path)?,
+            None => match Config::default_path().filter(|path| path.is_file()) {
+                Some(path) => Config::from_file(&path)?,
+                None => Config::default(),
+            },
+        };
+
+        config.merge_env(env::vars())
+    }
+
+    /// The default location of the config file, e.g. `~/.config/dachterasse/config.toml` on Linux
+    pub fn default_path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("dachterasse").join("config.toml"))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, Error> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read config file {}: {}", path.display(), err))?;
+        Config::from_toml(&content).map_err(|err| format!("Invalid config file {}: {}", path.display(), err))
+    }
+
+    /// Parses a config from TOML. Missing settings keep their default values.
+    pub fn from_toml(toml: &str) -> Result<Config, Error> {
+        let config: Config = toml::from_str(toml).map_err(|err| err.to_string())?;
+        config.validate()
+    }
+
+    /// Overrides settings with the given `DACHTERASSE_*` variables, ignoring all other variables
+    pub fn merge_env(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Result<Config, Error> {
+        for (key, value) in vars {
+            let Some(name) = key.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let invalid = |err: &dyn std::fmt::Display| format!("Invalid value '{}' of {}: {}", value, key, err);
+
+            match name {
+                "CACHE_DIR" => self.cache_path = Some(value.clone()).filter(|path| !path.is_empty()),
+                "OFFLINE" => self.offline = parse_bool(&value).ok_or_else(|| invalid(&"expected true or false"))?,
+                "CACHE_TTL" => {
+                    self.cache_ttl = Some(humantime::parse_duration(&value).map_err(|err| invalid(&err))?)
+                }
+                "DEGREES" => {
+                    self.degrees = Some(value.split(',').map(|id| id.trim().to_owned()).collect());
+                }
+                "CONCURRENCY" => self.concurrency = value.parse().map_err(|err| invalid(&err))?,
+                "TIMEOUT" => self.timeout = humantime::parse_duration(&value).map_err(|err| invalid(&err))?,
+                "USER_AGENT" => self.user_agent = value.clone(),
+                "CONTACT" => self.contact = Some(value.clone()).filter(|contact| !contact.is_empty()),
+                "RATE_LIMIT" => self.rate_limit = value.parse().map_err(|err| invalid(&err))?,
+                "RESPECT_ROBOTS_TXT" => {
+                    self.respect_robots_txt = parse_bool(&value).ok_or_else(|| invalid(&"expected true or false"))?
+                }
+                "DATABASE_URL" => self.database_url = Some(value.clone()).filter(|url| !url.is_empty()),
+                "ADMIN_TOKEN" => self.admin_token = Some(value.clone()).filter(|token| !token.is_empty()),
+                "API_DOCS" => self.api_docs = parse_bool(&value).ok_or_else(|| invalid(&"expected true or false"))?,
+                "BASE_URL" => self.base_url = value.clone(),
+                _ => {}
+            }
+        }
+
+        self.validate()
+    }
+
+    fn validate(mut self) -> Result<Config, Error> {
+        if self.concurrency == 0 {
+            return Err(String::from("concurrency must be at least 1"));
+        }
+        if !self.user_agent.chars().all(|c| c == ' ' || c.is_ascii_graphic()) {
+            return Err(String::from("user_agent may only contain printable ASCII characters"));
+        }
+        if !self
+            .contact
+            .iter()
+            .flat_map(|contact| contact.chars())
+            .all(|c| c == ' ' || c.is_ascii_graphic())
+        {
+            return Err(String::from("contact may only contain printable ASCII characters"));
+        }
+        if !self.rate_limit.is_finite() || self.rate_limit < 0.0 {
+            return Err(String::from("rate_limit must be a non-negative number of requests per second"));
+        }
+        if let Some(id) = self
+            .degrees
+            .iter()
+            .flatten()
+            .find(|id| Degrees::by_id(id).is_none())
+        {
+            return Err(format!("Unknown degree '{}'", id));
+        }
+        self.base_url = self.base_url.trim_end_matches('/').to_owned();
+        Ok(self)
+    }
+
     /// Set the cache path for this config
     pub fn cache_path(mut self, path: String) -> Self {
         self.cache_path = Some(path);
         self
     }
 
+    /// Only load lectures from the cache instead of scraping them
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Scrape cached lectures again once they are older than `ttl`
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Send at most `per_second` requests per second to the lecture site
+    pub fn rate_limit(mut self, per_second: f64) -> Self {
+        self.rate_limit = per_second;
+        self
+    }
+
+    /// Enable the server's admin endpoints for requests with the given bearer token
+    pub fn admin_token(mut self, token: &str) -> Self {
+        self.admin_token = Some(token.to_owned());
+        self
+    }
+
+    /// Serve the server's interactive API documentation at `/docs`
+    pub fn api_docs(mut self) -> Self {
+        self.api_docs = true;
+        self
+    }
+
+    /// Only load lectures for the given degrees
+    pub fn degrees(mut self, degrees: &[&StaticDegree]) -> Self {
+        self.degrees = Some(degrees.iter().map(|degree| degree.id.to_owned()).collect());
+        self
+    }
+
+    /// The `User-Agent` header sent by the scrapers, e.g. `dachterasse/0.1.4 (+mailto:lectures@example.org)`
+    pub fn user_agent_header(&self) -> String {
+        match &self.contact {
+            Some(contact) => format!("{} (+{})", self.user_agent, contact),
+            None => self.user_agent.clone(),
+        }
+    }
+
+    /// The product token of the user agent, which robots.txt rules are matched against
+    pub fn product_token(&self) -> &str {
+        self.user_agent.split(['/', ' ']).next().unwrap_or_default()
+    }
+
     pub fn get_cache_path(&self) -> &Option<String> {
         &self.cache_path
     }
-}
\ No newline at end of file
+
+    /// The degrees to load lectures for, in the order of `Degrees::all()`
+    pub fn enabled_degrees(&self) -> Vec<&'static StaticDegree> {
+        Degrees::all()
+            .iter()
+            .filter(|degree| self.is_enabled(degree))
+            .collect()
+    }
+
+    pub fn is_enabled(&self, degree: &StaticDegree) -> bool {
+        self.degrees
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == degree.id))
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_overrides_file() {
+        let config = Config::from_toml(
+            r#"
+            cache_dir = "/var/cache/dachterasse"
+            cache_ttl = "12h"
+            degrees = ["itse-ba"]
+            timeout = "10s"
+            "#,
+        )
+        .unwrap()
+        .merge_env([
+            (String::from("DACHTERASSE_CACHE_DIR"), String::from("/tmp/lectures")),
+            (String::from("DACHTERASSE_CONCURRENCY"), String::from("8")),
+            (String::from("HOME"), String::from("/home/student")),
+        ])
+        .unwrap();
+
+        assert_eq!(config.cache_path.as_deref(), Some("/tmp/lectures"));
+        assert_eq!(config.cache_ttl, Some(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(config.timeout, Duration::from_secs(10));
+        assert_eq!(config.concurrency, 8);
+        assert_eq!(config.enabled_degrees(), vec![&Degrees::ITSE_BA]);
+        assert_eq!(config.base_url, BASE_URL);
+        assert_eq!(config.product_token(), "dachterasse");
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(Config::from_toml("degrees = [\"unknown\"]").is_err());
+        assert!(Config::from_toml("cache_path = \"cache\"").is_err());
+        assert!(Config::new()
+            .merge_env([(String::from("DACHTERASSE_TIMEOUT"), String::from("soon"))])
+            .is_err());
+        assert!(Config::from_toml("rate_limit = -1.0").is_err());
+    }
+}
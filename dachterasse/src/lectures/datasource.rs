@@ -11,4 +11,9 @@ pub trait ReadOnlyDataSource: Send + Sync {
 
 pub trait ReadWriteDataSource: ReadOnlyDataSource {
     fn save_lectures(&mut self, degree: &'static StaticDegree, lectures: &[Lecture]) -> SaveResult;
+
+    /// Deletes the stored lectures of a degree, so that they are loaded from other sources again
+    fn clear_lectures(&mut self, _degree: &'static StaticDegree) -> SaveResult {
+        Err(String::from("Clearing is not supported by this data source"))
+    }
 }
@@ -1,12 +1,29 @@
+use super::schedule::Schedule;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
-#[derive(Serialize, Deserialize, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Lecture {
     pub title: String,
     pub url: String,
-    // pub description: Option<String>,
+    pub description: Option<String>,
     pub categories: Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub lecturers: Vec<String>,
+    pub ects: Option<u8>,
+    pub language: Option<Language>,
+    /// The semester in which the lecture takes place as stated on the lecture page, e.g. "Wintersemester 2022/23"
+    pub semester: Option<String>,
+    pub schedule: Option<Schedule>,
+}
+
+impl Lecture {
+    /// A short identifier for this lecture derived from its URL, which is the same across all degrees
+    pub fn id(&self) -> &str {
+        let name = self.url.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        name.strip_suffix(".html").unwrap_or(name)
+    }
 }
 
 impl PartialEq for Lecture {
@@ -21,20 +38,23 @@ impl Hash for Lecture {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DegreeLevel {
     Bachelor,
     Master,
 }
 
 // TODO: Serialize to commonly used language abbreviations
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Language {
     German,
     English,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct StaticDegree {
     /// The section headline string as found in the module overview for a lecture
     pub name: &'static str,
@@ -119,6 +139,11 @@ impl Degrees {
     pub fn all() -> &'static [StaticDegree] {
         Self::DEGREES
     }
+
+    /// Finds a degree by its id, e.g. "itse-ba"
+    pub fn by_id(id: &str) -> Option<&'static StaticDegree> {
+        Self::DEGREES.iter().find(|degree| degree.id == id)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -0,0 +1,59 @@
+use std::io::{self, Write};
+
+use super::format::module_rows;
+use super::Exporter;
+use crate::lectures::entities::Lecture;
+
+/// Exports lectures as comma-separated values with one row per lecture, module and category
+pub struct CsvExporter;
+
+const HEADER: [&str; 9] = [
+    "id", "title", "url", "ects", "language", "semester", "lecturers", "module", "category",
+];
+
+impl Exporter for CsvExporter {
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()> {
+        write_csv_row(out, &HEADER)?;
+        for lecture in lectures {
+            let ects = lecture.ects.map(|ects| ects.to_string()).unwrap_or_default();
+            let language = lecture.language.map(|l| format!("{:?}", l)).unwrap_or_default();
+            let lecturers = lecture.lecturers.join("; ");
+            for (module, category) in module_rows(lecture) {
+                write_csv_row(
+                    out,
+                    &[
+                        lecture.id(),
+                        &lecture.title,
+                        &lecture.url,
+                        &ects,
+                        &language,
+                        lecture.semester.as_deref().unwrap_or_default(),
+                        &lecturers,
+                        module,
+                        category,
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a CSV row, quoting fields where necessary
+pub fn write_csv_row(out: &mut dyn Write, fields: &[&str]) -> io::Result<()> {
+    let row = fields.iter().map(|field| quote(field)).collect::<Vec<_>>().join(",");
+    write!(out, "{}\r\n", row)
+}
+
+/// Quotes a field if it contains characters with a special meaning in CSV
+fn quote(field: &str) -> String {
+    if field.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use super::{CsvExporter, HtmlExporter, IcsExporter, JsonExporter, MarkdownExporter};
+use crate::lectures::entities::Lecture;
+
+/// Writes lectures in a specific file format
+pub trait Exporter {
+    /// The file extension commonly used for this format, without a leading dot
+    fn extension(&self) -> &'static str;
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()>;
+
+    /// Exports the lectures into a string
+    fn export_to_string(&self, lectures: &[Lecture]) -> io::Result<String> {
+        let mut out = Vec::new();
+        self.export(lectures, &mut out)?;
+        String::from_utf8(out).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// All export formats supported by this crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One row per lecture, module and category
+    Csv,
+    Markdown,
+    /// A standalone HTML page
+    Html,
+    Json,
+    PrettyJson,
+    /// Lecture schedules as iCalendar file
+    Ics,
+}
+
+impl Format {
+    pub const ALL: [Format; 6] = [
+        Format::Csv,
+        Format::Markdown,
+        Format::Html,
+        Format::Json,
+        Format::PrettyJson,
+        Format::Ics,
+    ];
+
+    /// Creates an exporter for this format with default settings
+    pub fn exporter(&self) -> Box<dyn Exporter> {
+        match self {
+            Format::Csv => Box::new(CsvExporter),
+            Format::Markdown => Box::new(MarkdownExporter),
+            Format::Html => Box::new(HtmlExporter::new()),
+            Format::Json => Box::new(JsonExporter::compact()),
+            Format::PrettyJson => Box::new(JsonExporter::pretty()),
+            Format::Ics => Box::new(IcsExporter::new()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Markdown => "markdown",
+            Format::Html => "html",
+            Format::Json => "json",
+            Format::PrettyJson => "json-pretty",
+            Format::Ics => "ics",
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "md" => Ok(Format::Markdown),
+            "htm" => Ok(Format::Html),
+            "pretty-json" | "json_pretty" => Ok(Format::PrettyJson),
+            "ical" | "icalendar" => Ok(Format::Ics),
+            name => Format::ALL
+                .into_iter()
+                .find(|format| format.name() == name)
+                .ok_or(format!("Unknown export format '{}'", name)),
+        }
+    }
+}
+
+/// Pairs of module and category in which a lecture can be credited, ordered by name.
+/// Yields an empty category for modules without categories and a single empty pair for lectures without modules.
+pub(crate) fn module_rows(lecture: &Lecture) -> impl Iterator<Item = (&str, &str)> {
+    let mut modules = lecture.categories.iter().flatten().collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut rows = Vec::new();
+    for (module, categories) in modules {
+        if categories.is_empty() {
+            rows.push((module.as_str(), ""));
+        }
+        rows.extend(categories.iter().map(|category| (module.as_str(), category.as_str())));
+    }
+    if rows.is_empty() {
+        rows.push(("", ""));
+    }
+    rows.into_iter()
+}
+
+/// Summarizes modules and categories of a lecture in one line, e.g. "Vertiefungsgebiete (BPET, OSIS)"
+pub(crate) fn modules_summary(lecture: &Lecture) -> String {
+    let mut modules = lecture
+        .categories
+        .iter()
+        .flatten()
+        .map(|(module, categories)| match categories.is_empty() {
+            true => module.clone(),
+            false => format!("{} ({})", module, categories.join(", ")),
+        })
+        .collect::<Vec<_>>();
+    modules.sort();
+    modules.join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn exports_rows_per_category() {
+        let lecture = Lecture {
+            title: String::from("Datenbanksysteme II, Teil 1"),
+            url: String::from("https://hpi.de/studium/dbs2.html"),
+            categories: Some(HashMap::from([(
+                String::from("Vertiefungsgebiete"),
+                vec![String::from("BPET"), String::from("ISAE")],
+            )])),
+            ects: Some(6),
+            ..Default::default()
+        };
+
+        let csv = Format::Csv.exporter().export_to_string(std::slice::from_ref(&lecture)).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            vec![
+                "id,title,url,ects,language,semester,lecturers,module,category",
+                "dbs2,\"Datenbanksysteme II, Teil 1\",https://hpi.de/studium/dbs2.html,6,,,,Vertiefungsgebiete,BPET",
+                "dbs2,\"Datenbanksysteme II, Teil 1\",https://hpi.de/studium/dbs2.html,6,,,,Vertiefungsgebiete,ISAE",
+            ]
+        );
+
+        let markdown = Format::Markdown.exporter().export_to_string(&[lecture]).unwrap();
+        assert!(markdown.contains(
+            "| [Datenbanksysteme II, Teil 1](https://hpi.de/studium/dbs2.html) | 6 |  | Vertiefungsgebiete (BPET, ISAE) |"
+        ));
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!("md".parse(), Ok(Format::Markdown));
+        assert_eq!("JSON-pretty".parse(), Ok(Format::PrettyJson));
+        assert!("pdf".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::{self, Write};
+
+use super::format::module_rows;
+use super::Exporter;
+use crate::lectures::entities::Lecture;
+
+/// Exports lectures as a standalone HTML page containing a table of all lectures
+pub struct HtmlExporter {
+    title: String,
+}
+
+impl Default for HtmlExporter {
+    fn default() -> Self {
+        HtmlExporter::new()
+    }
+}
+
+impl HtmlExporter {
+    pub fn new() -> Self {
+        HtmlExporter {
+            title: String::from("Lectures"),
+        }
+    }
+
+    /// Sets the page title
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #ccc;padding:.4em .6em;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}ul{margin:0;padding-left:1.2em}";
+
+impl Exporter for HtmlExporter {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()> {
+        let title = escape(&self.title);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>\n<style>{}</style>\n</head>\n<body>", title, STYLE)?;
+        writeln!(out, "<h1>{}</h1>\n<table>", title)?;
+        writeln!(out, "<tr><th>Title</th><th>ECTS</th><th>Lecturers</th><th>Modules</th></tr>")?;
+        for lecture in lectures {
+            let modules = module_rows(lecture)
+                .filter(|(module, _)| !module.is_empty())
+                .map(|(module, category)| match category {
+                    "" => format!("<li>{}</li>", escape(module)),
+                    category => format!("<li>{}: {}</li>", escape(module), escape(category)),
+                })
+                .collect::<String>();
+            writeln!(
+                out,
+                "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td><ul>{}</ul></td></tr>",
+                escape(&lecture.url),
+                escape(&lecture.title),
+                lecture.ects.map(|ects| ects.to_string()).unwrap_or_default(),
+                escape(&lecture.lecturers.join(", ")),
+                modules,
+            )?;
+        }
+        writeln!(out, "</table>\n</body>\n</html>")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,245 @@
+//! Export of lecture schedules as iCalendar files (RFC 5545)
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use super::Exporter;
+use crate::lectures::entities::Lecture;
+use crate::lectures::schedule::LecturePeriod;
+
+const TIMEZONE: &str = "Europe/Berlin";
+
+/// Definition of the timezone all lecture times are given in
+const VTIMEZONE: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:Europe/Berlin",
+    "BEGIN:DAYLIGHT",
+    "TZOFFSETFROM:+0100",
+    "TZOFFSETTO:+0200",
+    "TZNAME:CEST",
+    "DTSTART:19700329T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU",
+    "END:DAYLIGHT",
+    "BEGIN:STANDARD",
+    "TZOFFSETFROM:+0200",
+    "TZOFFSETTO:+0100",
+    "TZNAME:CET",
+    "DTSTART:19701025T030000",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "END:STANDARD",
+    "END:VTIMEZONE",
+];
+
+/// Writes the schedules of lectures as iCalendar events.
+///
+/// Weekly sessions become recurring events within the lecture period of the semester stated on the lecture page,
+/// falling back to the configured period. One-off dates become single events.
+pub struct IcsExporter {
+    name: Option<String>,
+    period: Option<LecturePeriod>,
+    timestamp: DateTime<Utc>,
+}
+
+impl Default for IcsExporter {
+    fn default() -> Self {
+        IcsExporter::new()
+    }
+}
+
+impl IcsExporter {
+    pub fn new() -> Self {
+        IcsExporter {
+            name: None,
+            period: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Sets the calendar name shown by calendar applications
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the lecture period used for lectures which do not state their semester.
+    /// Defaults to the current semester.
+    pub fn period(mut self, period: LecturePeriod) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Sets the creation time of all events. Defaults to the time the exporter was created.
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    fn write_events(&self, lecture: &Lecture, calendar: &mut Calendar) -> io::Result<()> {
+        let Some(schedule) = &lecture.schedule else {
+            return Ok(());
+        };
+        let period = lecture
+            .semester
+            .as_deref()
+            .and_then(LecturePeriod::from_semester)
+            .or(self.period)
+            .unwrap_or_else(LecturePeriod::current);
+
+        for (index, session) in schedule.sessions.iter().enumerate() {
+            let date = period.first(session.weekday);
+            self.write_event(
+                calendar,
+                lecture,
+                Event {
+                    uid: format!("{}-weekly-{}", lecture.id(), index),
+                    date,
+                    start: session.start,
+                    end: session.end,
+                    room: session.room.as_deref(),
+                    until: Some(period.end),
+                },
+            )?;
+        }
+        for (index, appointment) in schedule.dates.iter().enumerate() {
+            self.write_event(
+                calendar,
+                lecture,
+                Event {
+                    uid: format!("{}-once-{}", lecture.id(), index),
+                    date: appointment.date,
+                    start: appointment.start,
+                    end: appointment.end,
+                    room: appointment.room.as_deref(),
+                    until: None,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_event(&self, calendar: &mut Calendar, lecture: &Lecture, event: Event) -> io::Result<()> {
+        calendar.line("BEGIN:VEVENT")?;
+        calendar.line(&format!("UID:{}@dachterasse", event.uid))?;
+        calendar.line(&format!("DTSTAMP:{}", self.timestamp.format("%Y%m%dT%H%M%SZ")))?;
+        calendar.line(&format!("DTSTART;TZID={}:{}", TIMEZONE, local(event.date, event.start)))?;
+        calendar.line(&format!("DTEND;TZID={}:{}", TIMEZONE, local(event.date, event.end)))?;
+        if let Some(until) = event.until {
+            // UNTIL has to be given in UTC if the start is given with a timezone
+            calendar.line(&format!("RRULE:FREQ=WEEKLY;UNTIL={}T235959Z", until.format("%Y%m%d")))?;
+        }
+        calendar.property("SUMMARY", &lecture.title)?;
+        if let Some(room) = event.room {
+            calendar.property("LOCATION", room)?;
+        }
+        calendar.line(&format!("URL:{}", lecture.url))?;
+        calendar.property("DESCRIPTION", &lecture.url)?;
+        calendar.line("END:VEVENT")
+    }
+}
+
+impl Exporter for IcsExporter {
+    fn extension(&self) -> &'static str {
+        "ics"
+    }
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()> {
+        let mut calendar = Calendar { out };
+        calendar.line("BEGIN:VCALENDAR")?;
+        calendar.line("VERSION:2.0")?;
+        calendar.line("PRODID:-//dachterasse//HPI lectures//EN")?;
+        calendar.line("CALSCALE:GREGORIAN")?;
+        if let Some(name) = &self.name {
+            calendar.property("X-WR-CALNAME", name)?;
+        }
+        for line in VTIMEZONE {
+            calendar.line(line)?;
+        }
+        for lecture in lectures {
+            self.write_events(lecture, &mut calendar)?;
+        }
+        calendar.line("END:VCALENDAR")
+    }
+}
+
+struct Event<'a> {
+    uid: String,
+    date: NaiveDate,
+    start: NaiveTime,
+    end: NaiveTime,
+    room: Option<&'a str>,
+    until: Option<NaiveDate>,
+}
+
+fn local(date: NaiveDate, time: NaiveTime) -> String {
+    date.and_time(time).format("%Y%m%dT%H%M%S").to_string()
+}
+
+struct Calendar<'w> {
+    out: &'w mut dyn Write,
+}
+
+impl<'w> Calendar<'w> {
+    /// Writes a property with a text value, escaping special characters
+    fn property(&mut self, name: &str, value: &str) -> io::Result<()> {
+        self.line(&format!("{}:{}", name, escape(value)))
+    }
+
+    /// Writes a content line, folding it into multiple lines of at most 75 octets
+    fn line(&mut self, line: &str) -> io::Result<()> {
+        let mut start = 0;
+        let mut limit = 75;
+        while line.len() - start > limit {
+            let mut end = start + limit;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.out.write_all(&line.as_bytes()[start..end])?;
+            self.out.write_all(b"\r\n ")?;
+            start = end;
+            // Continuation lines start with a space which counts towards the limit
+            limit = 74;
+        }
+        self.out.write_all(&line.as_bytes()[start..])?;
+        self.out.write_all(b"\r\n")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lectures::schedule::parse_schedule;
+    use chrono::TimeZone;
+
+    #[test]
+    fn writes_recurring_events() {
+        let lecture = Lecture {
+            title: String::from("Datenbanksysteme I, Teil 1"),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            semester: Some(String::from("Wintersemester 2022/23")),
+            schedule: Some(parse_schedule(["Dienstag, 09:15 - 10:45 Uhr, HS 1"])),
+            ..Default::default()
+        };
+
+        let mut out = Vec::new();
+        IcsExporter::new()
+            .timestamp(Utc.with_ymd_and_hms(2022, 10, 1, 12, 0, 0).unwrap())
+            .export(&[lecture], &mut out)
+            .unwrap();
+        let ics = String::from_utf8(out).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;TZID=Europe/Berlin:20221018T091500\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;UNTIL=20230215T235959Z\r\n"));
+        assert!(ics.contains("SUMMARY:Datenbanksysteme I\\, Teil 1\r\n"));
+        assert!(ics.contains("LOCATION:HS 1\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
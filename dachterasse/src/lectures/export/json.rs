@@ -0,0 +1,35 @@
+use std::io::{self, Write};
+
+use super::Exporter;
+use crate::lectures::entities::Lecture;
+
+/// Exports lectures in the same JSON representation that is used for caching
+pub struct JsonExporter {
+    pretty: bool,
+}
+
+impl JsonExporter {
+    pub fn compact() -> Self {
+        JsonExporter { pretty: false }
+    }
+
+    /// Creates an exporter which writes indented JSON
+    pub fn pretty() -> Self {
+        JsonExporter { pretty: true }
+    }
+}
+
+impl Exporter for JsonExporter {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()> {
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut *out, lectures)?;
+        } else {
+            serde_json::to_writer(&mut *out, lectures)?;
+        }
+        writeln!(out)
+    }
+}
@@ -0,0 +1,38 @@
+use std::io::{self, Write};
+
+use super::format::modules_summary;
+use super::Exporter;
+use crate::lectures::entities::Lecture;
+
+/// Exports lectures as a Markdown table
+pub struct MarkdownExporter;
+
+impl Exporter for MarkdownExporter {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn export(&self, lectures: &[Lecture], out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "| Title | ECTS | Lecturers | Modules |")?;
+        writeln!(out, "| --- | --- | --- | --- |")?;
+        for lecture in lectures {
+            writeln!(
+                out,
+                "| [{}]({}) | {} | {} | {} |",
+                escape(&lecture.title),
+                lecture.url,
+                lecture.ects.map(|ects| ects.to_string()).unwrap_or_default(),
+                escape(&lecture.lecturers.join(", ")),
+                escape(&modules_summary(lecture)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace('\n', " ")
+}
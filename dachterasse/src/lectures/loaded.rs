@@ -0,0 +1,94 @@
+//! Lectures loaded by a client, shared by the sync and async `LectureClient`.
+//!
+//! Both clients dereference to [`LoadedLectures`], so querying and searching works the same way
+//! no matter how the lectures were loaded.
+
+use std::collections::HashMap;
+
+use super::catalogue::Catalogue;
+use super::entities::{Degrees, Lecture, StaticDegree};
+use super::query::{Filter, Query, QueryResult};
+use super::schedule::Timetable;
+use super::search::{SearchHit, SearchIndex};
+
+/// The lectures of all degrees, which are empty until they are loaded
+pub struct LoadedLectures {
+    lectures: HashMap<&'static StaticDegree, Vec<Lecture>>,
+}
+
+impl Default for LoadedLectures {
+    fn default() -> Self {
+        LoadedLectures {
+            lectures: Degrees::all().iter().map(|degree| (degree, Vec::new())).collect(),
+        }
+    }
+}
+
+impl LoadedLectures {
+    /// Replaces the lectures of a degree and returns them
+    pub fn insert(&mut self, degree: &'static StaticDegree, lectures: Vec<Lecture>) -> &[Lecture] {
+        self.lectures.insert(degree, lectures);
+        &self.lectures[degree]
+    }
+
+    /// Returns lectures if the client was already initialized or an empty slice otherwise
+    pub fn lectures(&self, degree: &'static StaticDegree) -> &[Lecture] {
+        &self.lectures[degree]
+    }
+
+    /// Returns all lectures that match the given search criteria
+    ///
+    /// # Arguments
+    ///
+    /// * `modules` - Search for all lectures matching any of the given module names literally
+    pub fn filter_lectures(
+        &self,
+        modules: Vec<&str>,
+        degree: &'static StaticDegree,
+    ) -> Vec<&Lecture> {
+        self.query(&Query::new().degree(degree).filter(Filter::any_module(modules)))
+            .map(|m| m.lecture)
+            .collect()
+    }
+
+    /// Returns an iterator over all loaded lectures that match the given query
+    pub fn query(&self, query: &Query) -> QueryResult<'_> {
+        query.run(&self.lectures)
+    }
+
+    /// Builds a full-text index over all loaded lectures of all degrees
+    pub fn search_index(&self) -> SearchIndex<'_> {
+        SearchIndex::new(
+            Degrees::all()
+                .iter()
+                .flat_map(|degree| self.lectures[degree].iter()),
+        )
+    }
+
+    /// Searches titles, descriptions and modules of all loaded lectures and returns them ordered by relevance
+    pub fn search(&self, text: &str) -> Vec<SearchHit<'_>> {
+        self.search_index().search(text)
+    }
+
+    /// Returns all loaded lectures merged across degrees, listing each lecture once with its module placements per degree
+    pub fn catalogue(&self) -> Catalogue {
+        Catalogue::from_lectures(&self.lectures)
+    }
+
+    /// Finds a loaded lecture by its id as returned by `Lecture::id`
+    pub fn lecture(&self, id: &str) -> Option<&Lecture> {
+        Degrees::all()
+            .iter()
+            .flat_map(|degree| self.lectures[degree].iter())
+            .find(|lecture| lecture.id() == id)
+    }
+
+    /// Creates a timetable of the lectures with the given ids, e.g. to check them for conflicts
+    pub fn timetable(&self, ids: &[&str]) -> Result<Timetable<'_>, String> {
+        let lectures = ids
+            .iter()
+            .map(|id| self.lecture(id).ok_or(format!("No lecture with id {} found", id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Timetable::new(lectures))
+    }
+}
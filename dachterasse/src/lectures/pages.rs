@@ -0,0 +1,165 @@
+//! Validators of scraped pages, used to send conditional requests when scraping again.
+//!
+//! Pages answered with `304 Not Modified` are not parsed again. Their content is reused as parsed
+//! on the previous scrape instead.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+use super::entities::Lecture;
+use super::parse::LectureDetails;
+
+/// Name of the file in the cache directory in which the validators of scraped pages are stored
+pub const PAGE_CACHE_FILE: &str = "pages.json";
+
+/// The parsed content of a page together with the validators the server sent for it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CachedPage<T> {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content: T,
+}
+
+impl<T> CachedPage<T> {
+    /// Creates a page from the headers of the response it was parsed from
+    pub fn new(headers: &HeaderMap, content: T) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_owned)
+        };
+
+        CachedPage {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            content,
+        }
+    }
+
+    /// Whether the server sent any validators, i.e. whether the page can be requested conditionally
+    pub fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Headers of a request which the server answers with `304 Not Modified` if the page did not change
+    pub fn conditions(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let values = [(IF_NONE_MATCH, &self.etag), (IF_MODIFIED_SINCE, &self.last_modified)];
+        for (name, value) in values {
+            if let Some(value) = value.as_deref().and_then(|value| HeaderValue::from_str(value).ok()) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+struct Pages {
+    /// Lecture overview pages by URL
+    overviews: HashMap<String, CachedPage<Vec<Lecture>>>,
+    /// Lecture detail pages by URL and degree name, since details are parsed for one degree at a time
+    details: HashMap<String, HashMap<String, CachedPage<LectureDetails>>>,
+}
+
+/// The pages fetched by a scraper, optionally persisted as a JSON file so that later runs can
+/// request them conditionally as well
+#[derive(Default)]
+pub struct PageCache {
+    pages: Mutex<Pages>,
+    path: Option<PathBuf>,
+}
+
+impl PageCache {
+    /// Creates a cache which is only kept in memory
+    pub fn new() -> Self {
+        PageCache::default()
+    }
+
+    /// Creates a cache which is loaded from the given file if it exists and written to it by [`PageCache::save`].
+    /// An unreadable file is treated like an empty cache, so all pages are fetched again.
+    pub fn persistent<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let pages = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        PageCache {
+            pages: Mutex::new(pages),
+            path: Some(path),
+        }
+    }
+
+    pub fn overview(&self, url: &str) -> Option<CachedPage<Vec<Lecture>>> {
+        self.pages().overviews.get(url).cloned()
+    }
+
+    /// Stores the page if it can be requested conditionally
+    pub fn set_overview(&self, url: &str, page: &CachedPage<Vec<Lecture>>) {
+        if page.has_validators() {
+            self.pages().overviews.insert(url.to_owned(), page.clone());
+        }
+    }
+
+    pub fn details(&self, url: &str, degree: &str) -> Option<CachedPage<LectureDetails>> {
+        self.pages().details.get(url)?.get(degree).cloned()
+    }
+
+    /// Stores the page if it can be requested conditionally
+    pub fn set_details(&self, url: &str, degree: &str, page: &CachedPage<LectureDetails>) {
+        if page.has_validators() {
+            self.pages()
+                .details
+                .entry(url.to_owned())
+                .or_default()
+                .insert(degree.to_owned(), page.clone());
+        }
+    }
+
+    /// Writes the cache to its file. Does nothing for caches which are only kept in memory.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(directories) = path.parent() {
+            fs::create_dir_all(directories)?;
+        }
+        let content = serde_json::to_string(&*self.pages())?;
+        fs::write(path, content)
+    }
+
+    fn pages(&self) -> std::sync::MutexGuard<'_, Pages> {
+        self.pages.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sends_validators_of_previous_response() {
+        let mut response = HeaderMap::new();
+        response.insert(ETAG, HeaderValue::from_static("\"33a64df5\""));
+        response.insert(LAST_MODIFIED, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+
+        let page = CachedPage::new(&response, vec![Lecture::default()]);
+        let cache = PageCache::new();
+        cache.set_overview("https://hpi.de/studium", &page);
+        let conditions = cache.overview("https://hpi.de/studium").unwrap().conditions();
+
+        assert_eq!(conditions.get(IF_NONE_MATCH).unwrap(), "\"33a64df5\"");
+        assert_eq!(conditions.get(IF_MODIFIED_SINCE).unwrap(), "Wed, 21 Oct 2015 07:28:00 GMT");
+
+        let without_validators = CachedPage::new(&HeaderMap::new(), LectureDetails::default());
+        cache.set_details("https://hpi.de/dbs1.html", "IT-Systems Engineering BA", &without_validators);
+        assert!(cache.details("https://hpi.de/dbs1.html", "IT-Systems Engineering BA").is_none());
+    }
+}
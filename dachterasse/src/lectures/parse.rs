@@ -0,0 +1,322 @@
+//! Parsing of the HPI lecture overview and lecture detail pages.
+//!
+//! Kept separate from fetching so that the sync and async scrapers share the same parsing logic.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+use super::entities::{Language, Lecture};
+use super::schedule::{parse_schedule, Schedule};
+
+pub const BASE_URL: &str = "https://hpi.de";
+
+/// Lecture information found on a lecture detail page
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct LectureDetails {
+    pub description: Option<String>,
+    pub categories: Option<HashMap<String, Vec<String>>>,
+    pub lecturers: Vec<String>,
+    pub ects: Option<u8>,
+    pub language: Option<Language>,
+    pub semester: Option<String>,
+    pub schedule: Option<Schedule>,
+}
+
+impl LectureDetails {
+    pub fn apply_to(self, lecture: &mut Lecture) {
+        lecture.description = self.description;
+        lecture.categories = self.categories;
+        lecture.lecturers = self.lecturers;
+        lecture.ects = self.ects;
+        lecture.language = self.language;
+        lecture.semester = self.semester;
+        lecture.schedule = self.schedule;
+    }
+}
+
+/// Replaces the default base URL of an absolute URL with the given one, e.g. to scrape a mirror
+pub fn resolve_url(url: &str, base_url: &str) -> String {
+    match url.strip_prefix(BASE_URL) {
+        Some(path) => format!("{}{}", base_url, path),
+        None => url.to_owned(),
+    }
+}
+
+/// Parses all lecture links from a degree's lecture overview page, resolving them against the given base URL
+pub fn parse_lectures(document: &str, base_url: &str) -> Vec<Lecture> {
+    let fragment = Html::parse_document(document);
+    let selector = Selector::parse("a.courselink").unwrap();
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let link_regex = LINK.get_or_init(|| Regex::new(r"/studium.*\.html").unwrap());
+
+    fragment
+        .select(&selector)
+        .filter_map(|element| {
+            let link = link_regex.captures(&element.html())?.get(0)?.as_str().to_owned();
+            Some(Lecture {
+                title: first_text(&element),
+                url: String::from(base_url) + &link,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses the details of a single lecture page as seen from the given degree
+pub fn parse_lecture_details(document: &str, degree: &str) -> LectureDetails {
+    let fragment = Html::parse_document(document);
+    let entries = labelled_entries(&fragment);
+
+    LectureDetails {
+        description: parse_description(&fragment),
+        categories: parse_categories(&fragment, degree),
+        lecturers: entries
+            .iter()
+            .find(|(label, _)| is_lecturer_label(label))
+            .map(|(_, value)| split_names(value))
+            .unwrap_or_default(),
+        ects: entries
+            .iter()
+            .find(|(label, _)| label.eq_ignore_ascii_case("ECTS"))
+            .and_then(|(_, value)| leading_number(value)),
+        language: entries
+            .iter()
+            .find(|(label, _)| is_language_label(label))
+            .and_then(|(_, value)| parse_language(value)),
+        semester: parse_semester(&fragment),
+        schedule: parse_dates(&fragment),
+    }
+}
+
+fn parse_categories(fragment: &Html, degree: &str) -> Option<HashMap<String, Vec<String>>> {
+    let selector = Selector::parse("div.tx_dscclipclap").unwrap();
+    let header_selector = Selector::parse("div.tx_dscclipclap_header").unwrap();
+    let item_selector = Selector::parse("li").unwrap();
+
+    let section = fragment.select(&selector).find(|element| {
+        element
+            .select(&header_selector)
+            .any(|inner| first_text(&inner) == degree)
+    })?;
+
+    let mut category_map = HashMap::<String, Vec<String>>::new();
+    for element in section.select(&item_selector) {
+        let mut children = element
+            .select(&item_selector)
+            .map(|child| first_text(&child))
+            .collect::<Vec<_>>();
+        if !children.is_empty() {
+            category_map
+                .entry(first_text(&element))
+                .or_default()
+                .append(&mut children);
+        }
+    }
+    Some(category_map)
+}
+
+/// Returns all elements following the heading with one of the given names up to the next heading
+fn section<'a>(fragment: &'a Html, names: &[&str]) -> Vec<ElementRef<'a>> {
+    let heading_selector = Selector::parse("h2, h3").unwrap();
+    let is_heading = |element: &ElementRef| matches!(element.value().name(), "h1" | "h2" | "h3");
+
+    let heading = fragment
+        .select(&heading_selector)
+        .find(|element| names.contains(&full_text(element).to_lowercase().as_str()));
+
+    match heading {
+        Some(heading) => heading
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take_while(|element| !is_heading(element))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn parse_description(fragment: &Html) -> Option<String> {
+    let paragraphs = section(fragment, &["beschreibung", "description"])
+        .iter()
+        .map(full_text)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>();
+
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+/// Parses the sessions listed in the dates section, where each list item or table row is one entry
+fn parse_dates(fragment: &Html) -> Option<Schedule> {
+    let entry_selector = Selector::parse("li, tr").unwrap();
+
+    let lines = section(fragment, &["termine", "dates", "schedule"])
+        .iter()
+        .flat_map(|element| {
+            let entries = element.select(&entry_selector).map(|entry| full_text(&entry)).collect::<Vec<_>>();
+            if entries.is_empty() {
+                vec![full_text(element)]
+            } else {
+                entries
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let schedule = parse_schedule(lines.iter().map(String::as_str));
+    if schedule.is_empty() {
+        None
+    } else {
+        Some(schedule)
+    }
+}
+
+/// Collects all "Label: Value" pairs from list items and paragraphs
+fn labelled_entries(fragment: &Html) -> Vec<(String, String)> {
+    let selector = Selector::parse("li, p, tr").unwrap();
+
+    fragment
+        .select(&selector)
+        .filter_map(|element| {
+            let text = full_text(&element);
+            let (label, value) = text.split_once(':')?;
+            let label = label.trim();
+            let value = value.trim();
+            if label.is_empty() || value.is_empty() || label.len() > 40 {
+                None
+            } else {
+                Some((label.to_owned(), value.to_owned()))
+            }
+        })
+        .collect()
+}
+
+fn is_lecturer_label(label: &str) -> bool {
+    ["dozent", "dozentin", "dozenten", "dozierende", "lecturer", "lecturers"]
+        .contains(&label.to_lowercase().as_str())
+}
+
+fn is_language_label(label: &str) -> bool {
+    ["lehrsprache", "sprache", "language", "teaching language"].contains(&label.to_lowercase().as_str())
+}
+
+fn parse_language(value: &str) -> Option<Language> {
+    let value = value.to_lowercase();
+    if value.starts_with("deutsch") || value.starts_with("german") {
+        Some(Language::German)
+    } else if value.starts_with("englisch") || value.starts_with("english") {
+        Some(Language::English)
+    } else {
+        None
+    }
+}
+
+fn parse_semester(fragment: &Html) -> Option<String> {
+    let selector = Selector::parse("h1").unwrap();
+    static SEMESTER: OnceLock<Regex> = OnceLock::new();
+    let semester_regex = SEMESTER
+        .get_or_init(|| Regex::new(r"(Winter|Sommer|Summer)semester \d{4}(/\d{2,4})?").unwrap());
+
+    fragment
+        .select(&selector)
+        .find_map(|element| semester_regex.find(&full_text(&element)).map(|m| m.as_str().to_owned()))
+}
+
+fn split_names(value: &str) -> Vec<String> {
+    static SEPARATOR: OnceLock<Regex> = OnceLock::new();
+    let separator = SEPARATOR.get_or_init(|| Regex::new(r"\s*(?:,|;|\bund\b|\band\b)\s*").unwrap());
+    separator
+        .split(value)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn leading_number(value: &str) -> Option<u8> {
+    value
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty())?
+        .parse()
+        .ok()
+}
+
+/// The first text node of an element, which excludes the text of nested lists
+pub fn first_text(element: &ElementRef) -> String {
+    element
+        .text()
+        .map(str::trim)
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// The complete text of an element with whitespace collapsed
+pub fn full_text(element: &ElementRef) -> String {
+    element.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DETAILS: &str = r#"
+        <html><body>
+        <h1>Datenbanksysteme I (Sommersemester 2023)</h1>
+        <p><b>Dozent:</b> Prof. Dr. Felix Naumann, Dr. Thorsten Papenbrock</p>
+        <h2>Allgemeine Information</h2>
+        <ul>
+            <li><b>Semesterwochenstunden</b>: 4</li>
+            <li><b>ECTS</b>: 6</li>
+            <li><b>Lehrsprache</b>: Deutsch</li>
+        </ul>
+        <div class="tx_dscclipclap">
+            <div class="tx_dscclipclap_header">IT-Systems Engineering BA</div>
+            <ul>
+                <li>Softwarebasissysteme
+                    <ul><li>Datenbanken und Informationssysteme</li></ul>
+                </li>
+            </ul>
+        </div>
+        <h2>Beschreibung</h2>
+        <p>Die Vorlesung behandelt relationale Datenbanken.</p>
+        <p>Dazu gehören SQL und Anfrageoptimierung.</p>
+        <h2>Termine</h2>
+        <ul>
+            <li>Dienstag, 09:15 - 10:45 Uhr, HS 1</li>
+            <li>Donnerstag, 11:00 - 12:30 Uhr, HS 1</li>
+        </ul>
+        <h2>Leistungserfassung</h2>
+        <p>Klausur</p>
+        </body></html>
+    "#;
+
+    #[test]
+    fn parses_details() {
+        let details = parse_lecture_details(DETAILS, "IT-Systems Engineering BA");
+
+        assert_eq!(details.ects, Some(6));
+        assert_eq!(
+            details.description.as_deref(),
+            Some("Die Vorlesung behandelt relationale Datenbanken.\n\nDazu gehören SQL und Anfrageoptimierung.")
+        );
+        assert_eq!(details.language, Some(Language::German));
+        assert_eq!(details.schedule.unwrap().sessions.len(), 2);
+        assert_eq!(details.semester.as_deref(), Some("Sommersemester 2023"));
+        assert_eq!(details.lecturers, vec!["Prof. Dr. Felix Naumann", "Dr. Thorsten Papenbrock"]);
+        assert_eq!(
+            details.categories.unwrap()["Softwarebasissysteme"],
+            vec!["Datenbanken und Informationssysteme"]
+        );
+    }
+
+    #[test]
+    fn ignores_other_degrees() {
+        let details = parse_lecture_details(DETAILS, "Data Engineering MA");
+        assert!(details.categories.is_none());
+    }
+}
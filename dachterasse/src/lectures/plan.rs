@@ -0,0 +1,443 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use super::entities::{Lecture, StaticDegree};
+
+/// Degree rules shipped with this crate, keyed by degree id
+const RULES: &str = include_str!("rules.json");
+
+/// Credit requirements of a degree which a study plan is checked against
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DegreeRules {
+    /// ECTS required in total from lectures, excluding the thesis
+    pub total_ects: u16,
+    /// How often a single lecture can be credited, e.g. when it is taken in several semesters
+    #[serde(default = "default_max_credits")]
+    pub max_credits_per_lecture: u8,
+    #[serde(default)]
+    pub groups: Vec<ModuleGroup>,
+    pub specializations: Option<SpecializationRule>,
+}
+
+fn default_max_credits() -> u8 {
+    1
+}
+
+/// A group of modules with a required amount of ECTS
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModuleGroup {
+    pub name: String,
+    /// Module names as found in the lecture categories which are credited to this group
+    pub modules: Vec<String>,
+    pub min_ects: u16,
+    /// ECTS exceeding this limit are not credited to the group
+    pub max_ects: Option<u16>,
+}
+
+/// Requirement to specialize in a number of categories (Vertiefungsgebiete) of a module
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpecializationRule {
+    /// The module whose categories are the specializations
+    pub module: String,
+    /// Number of specializations that have to be completed
+    pub count: u8,
+    /// ECTS required in each completed specialization
+    pub min_ects_each: u16,
+}
+
+impl DegreeRules {
+    /// Returns the rules for the given degree as shipped with this crate. Fails for degrees whose rules
+    /// are not known yet.
+    pub fn for_degree(degree: &StaticDegree) -> Result<&'static DegreeRules, String> {
+        static SHIPPED: OnceLock<HashMap<String, DegreeRules>> = OnceLock::new();
+        SHIPPED
+            .get_or_init(|| Self::from_json(RULES).expect("shipped degree rules are valid"))
+            .get(degree.id)
+            .ok_or_else(|| format!("No credit rules are known for the degree '{}'", degree.name))
+    }
+
+    /// Parses rules from a JSON object mapping degree ids to rules
+    pub fn from_json(json: &str) -> Result<HashMap<String, DegreeRules>, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// A lecture chosen for a study plan
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlannedLecture {
+    pub lecture: Lecture,
+    /// The module in which the lecture should be credited. Chosen automatically if not set.
+    pub module: Option<String>,
+    /// The category in which the lecture should be credited. Chosen automatically if not set.
+    pub category: Option<String>,
+}
+
+/// A selection of lectures per semester for one degree
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StudyPlan {
+    /// Id of the degree this plan is made for
+    pub degree: String,
+    semesters: BTreeMap<u8, Vec<PlannedLecture>>,
+}
+
+impl StudyPlan {
+    pub fn new(degree: &StaticDegree) -> Self {
+        StudyPlan {
+            degree: degree.id.to_owned(),
+            semesters: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a lecture to the given semester, letting the plan choose the module it is credited in
+    pub fn add(&mut self, semester: u8, lecture: Lecture) {
+        self.add_planned(
+            semester,
+            PlannedLecture {
+                lecture,
+                module: None,
+                category: None,
+            },
+        );
+    }
+
+    /// Adds a lecture to the given semester which should be credited in the given module and category
+    pub fn add_as(&mut self, semester: u8, lecture: Lecture, module: &str, category: Option<&str>) {
+        self.add_planned(
+            semester,
+            PlannedLecture {
+                lecture,
+                module: Some(module.to_owned()),
+                category: category.map(String::from),
+            },
+        );
+    }
+
+    pub fn add_planned(&mut self, semester: u8, lecture: PlannedLecture) {
+        self.semesters.entry(semester).or_default().push(lecture);
+    }
+
+    /// Removes a lecture from the given semester. Returns whether the lecture was planned.
+    pub fn remove(&mut self, semester: u8, url: &str) -> bool {
+        let Some(lectures) = self.semesters.get_mut(&semester) else {
+            return false;
+        };
+        let count = lectures.len();
+        lectures.retain(|planned| planned.lecture.url != url);
+        count != lectures.len()
+    }
+
+    /// The planned lectures of a semester
+    pub fn semester(&self, semester: u8) -> &[PlannedLecture] {
+        self.semesters.get(&semester).map_or(&[], Vec::as_slice)
+    }
+
+    /// All planned lectures together with their semester, ordered by semester
+    pub fn lectures(&self) -> impl Iterator<Item = (u8, &PlannedLecture)> {
+        self.semesters
+            .iter()
+            .flat_map(|(semester, lectures)| lectures.iter().map(move |lecture| (*semester, lecture)))
+    }
+
+    /// Checks the plan against the given rules and reports which requirements are still missing
+    pub fn check(&self, rules: &DegreeRules) -> PlanReport {
+        let mut report = PlanReport {
+            total_ects: 0,
+            groups: rules
+                .groups
+                .iter()
+                .map(|group| GroupProgress {
+                    name: group.name.clone(),
+                    ects: 0,
+                    min_ects: group.min_ects,
+                })
+                .collect(),
+            specializations: BTreeMap::new(),
+            missing: Vec::new(),
+            issues: Vec::new(),
+        };
+        let mut credited = HashMap::<&str, u8>::new();
+
+        for (semester, planned) in self.lectures() {
+            let lecture = &planned.lecture;
+            let times = credited.get(lecture.url.as_str()).copied().unwrap_or_default();
+            if times >= rules.max_credits_per_lecture {
+                report.issues.push(PlanIssue::CountedTooOften {
+                    semester,
+                    title: lecture.title.clone(),
+                });
+                continue;
+            }
+
+            let Some(ects) = lecture.ects else {
+                report.issues.push(PlanIssue::UnknownEcts {
+                    semester,
+                    title: lecture.title.clone(),
+                });
+                continue;
+            };
+            let ects = u16::from(ects);
+
+            let Some((group, module)) = choose_group(rules, &report, planned) else {
+                report.issues.push(PlanIssue::NotCredited {
+                    semester,
+                    title: lecture.title.clone(),
+                });
+                continue;
+            };
+
+            // Groups which are full are never chosen, so the lecture is credited at least one ECTS
+            let ects = rules.groups[group]
+                .max_ects
+                .map_or(ects, |max| ects.min(max - report.groups[group].ects));
+            report.total_ects += ects;
+            report.groups[group].ects += ects;
+            *credited.entry(&lecture.url).or_default() += 1;
+
+            if let Some(rule) = &rules.specializations {
+                if module.eq_ignore_ascii_case(&rule.module) {
+                    if let Some(category) = choose_category(rule, &report, planned, module) {
+                        *report.specializations.entry(category).or_default() += ects;
+                    }
+                }
+            }
+        }
+
+        report.collect_missing(rules);
+        report
+    }
+}
+
+/// Picks the group in which a lecture is credited, preferring the group which is furthest from completion
+fn choose_group<'p>(rules: &DegreeRules, report: &PlanReport, planned: &'p PlannedLecture) -> Option<(usize, &'p str)> {
+    let modules = planned
+        .lecture
+        .categories
+        .iter()
+        .flatten()
+        .map(|(module, _)| module.as_str())
+        .filter(|module| planned.module.as_ref().is_none_or(|chosen| chosen.eq_ignore_ascii_case(module)));
+
+    modules
+        .flat_map(|module| {
+            rules
+                .groups
+                .iter()
+                .enumerate()
+                .filter(move |(_, group)| group.modules.iter().any(|m| m.eq_ignore_ascii_case(module)))
+                .map(move |(index, group)| (index, group, module))
+        })
+        .filter(|(index, group, _)| group.max_ects.is_none_or(|max| report.groups[*index].ects < max))
+        .max_by_key(|(index, _, _)| report.groups[*index].remaining())
+        .map(|(index, _, module)| (index, module))
+}
+
+/// Picks the specialization in which a lecture is credited, preferring the one with the most ECTS so far
+fn choose_category(rule: &SpecializationRule, report: &PlanReport, planned: &PlannedLecture, module: &str) -> Option<String> {
+    if let Some(category) = &planned.category {
+        return Some(category.clone());
+    }
+    let categories = planned.lecture.categories.as_ref()?.get(module)?;
+    categories
+        .iter()
+        .filter(|category| report.specializations.get(*category).is_none_or(|ects| *ects < rule.min_ects_each))
+        .max_by_key(|category| report.specializations.get(*category).copied().unwrap_or_default())
+        .or_else(|| categories.first())
+        .cloned()
+}
+
+/// Progress of a study plan towards the requirements of a degree
+#[derive(Serialize, Clone, Debug)]
+pub struct PlanReport {
+    pub total_ects: u16,
+    pub groups: Vec<GroupProgress>,
+    /// ECTS credited per specialization
+    pub specializations: BTreeMap<String, u16>,
+    /// Requirements which are not fulfilled yet
+    pub missing: Vec<Requirement>,
+    /// Planned lectures which could not be credited
+    pub issues: Vec<PlanIssue>,
+}
+
+impl PlanReport {
+    /// Whether all requirements of the degree are fulfilled
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    fn collect_missing(&mut self, rules: &DegreeRules) {
+        if self.total_ects < rules.total_ects {
+            self.missing.push(Requirement::TotalEcts {
+                missing: rules.total_ects - self.total_ects,
+            });
+        }
+        for group in &self.groups {
+            if group.remaining() > 0 {
+                self.missing.push(Requirement::ModuleGroup {
+                    name: group.name.clone(),
+                    missing: group.remaining(),
+                });
+            }
+        }
+        if let Some(rule) = &rules.specializations {
+            let completed = self
+                .specializations
+                .values()
+                .filter(|ects| **ects >= rule.min_ects_each)
+                .count();
+            if completed < usize::from(rule.count) {
+                self.missing.push(Requirement::Specializations {
+                    missing: rule.count - completed as u8,
+                    min_ects_each: rule.min_ects_each,
+                });
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GroupProgress {
+    pub name: String,
+    pub ects: u16,
+    pub min_ects: u16,
+}
+
+impl GroupProgress {
+    pub fn remaining(&self) -> u16 {
+        self.min_ects.saturating_sub(self.ects)
+    }
+}
+
+/// A degree requirement which a study plan does not fulfill yet
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    TotalEcts { missing: u16 },
+    ModuleGroup { name: String, missing: u16 },
+    Specializations { missing: u8, min_ects_each: u16 },
+}
+
+/// A planned lecture which does not count towards the degree
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum PlanIssue {
+    /// The lecture was already credited as often as allowed
+    CountedTooOften { semester: u8, title: String },
+    /// The lecture page does not state its ECTS
+    UnknownEcts { semester: u8, title: String },
+    /// The lecture is not part of any module group of the degree or all of its groups are full
+    NotCredited { semester: u8, title: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Degrees;
+
+    fn lecture(title: &str, module: &str, categories: &[&str], ects: u8) -> Lecture {
+        Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/{}.html", title),
+            categories: Some(HashMap::from([(
+                module.to_owned(),
+                categories.iter().map(|c| c.to_string()).collect(),
+            )])),
+            ects: Some(ects),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_missing_requirements() {
+        let rules = DegreeRules {
+            total_ects: 24,
+            max_credits_per_lecture: 1,
+            groups: vec![
+                ModuleGroup {
+                    name: String::from("Vertiefungsgebiete"),
+                    modules: vec![String::from("Vertiefungsgebiete")],
+                    min_ects: 18,
+                    max_ects: None,
+                },
+                ModuleGroup {
+                    name: String::from("Softskills"),
+                    modules: vec![String::from("Softskills")],
+                    min_ects: 6,
+                    max_ects: Some(6),
+                },
+            ],
+            specializations: Some(SpecializationRule {
+                module: String::from("Vertiefungsgebiete"),
+                count: 2,
+                min_ects_each: 6,
+            }),
+        };
+
+        let mut plan = StudyPlan::new(&Degrees::ITSE_MA);
+        plan.add(1, lecture("Datenbanksysteme II", "Vertiefungsgebiete", &["ISAE"], 6));
+        plan.add(1, lecture("Compilerbau", "Vertiefungsgebiete", &["OSIS", "ISAE"], 6));
+        plan.add(2, lecture("Datenbanksysteme II", "Vertiefungsgebiete", &["ISAE"], 6));
+        plan.add(2, lecture("Projektmanagement", "Softskills", &[], 6));
+        plan.add(2, lecture("Präsentieren", "Softskills", &[], 3));
+
+        let report = plan.check(&rules);
+
+        assert_eq!(report.total_ects, 18);
+        assert_eq!(report.specializations["ISAE"], 6);
+        assert_eq!(report.specializations["OSIS"], 6);
+        assert_eq!(
+            report.missing,
+            vec![
+                Requirement::TotalEcts { missing: 6 },
+                Requirement::ModuleGroup {
+                    name: String::from("Vertiefungsgebiete"),
+                    missing: 6
+                },
+            ]
+        );
+        assert_eq!(report.issues.len(), 2);
+    }
+
+    #[test]
+    fn credits_at_most_the_group_limit() {
+        let rules = DegreeRules {
+            total_ects: 12,
+            max_credits_per_lecture: 1,
+            groups: vec![ModuleGroup {
+                name: String::from("Softskills"),
+                modules: vec![String::from("Softskills")],
+                min_ects: 6,
+                max_ects: Some(6),
+            }],
+            specializations: None,
+        };
+
+        let mut plan = StudyPlan::new(&Degrees::ITSE_MA);
+        plan.add(1, lecture("Präsentieren", "Softskills", &[], 3));
+        plan.add(1, lecture("Projektmanagement", "Softskills", &[], 6));
+        plan.add(2, lecture("Verhandeln", "Softskills", &[], 3));
+        plan.add(3, lecture("Verhandeln", "Softskills", &[], 3));
+
+        let report = plan.check(&rules);
+
+        assert_eq!(report.total_ects, 6);
+        assert_eq!(report.groups[0].ects, 6);
+        assert_eq!(
+            report.issues,
+            vec![
+                PlanIssue::NotCredited {
+                    semester: 2,
+                    title: String::from("Verhandeln")
+                },
+                PlanIssue::NotCredited {
+                    semester: 3,
+                    title: String::from("Verhandeln")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ships_rules_for_degrees() {
+        assert!(DegreeRules::for_degree(&Degrees::ITSE_BA).is_ok());
+        assert!(DegreeRules::for_degree(&Degrees::ITSE_MA).is_ok());
+        assert!(DegreeRules::for_degree(&Degrees::DE_MA).is_err());
+    }
+}
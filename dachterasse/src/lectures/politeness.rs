@@ -0,0 +1,296 @@
+//! Politeness controls shared by the sync and async scrapers: a rate limit for all requests to the
+//! lecture site and enforcement of its robots.txt.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use reqwest::{StatusCode, Url};
+
+/// Time after which the robots.txt of the lecture site is fetched again
+pub const ROBOTS_TXT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug)]
+pub enum ScrapeError {
+    Http(reqwest::Error),
+    /// The lecture site answered the request for the given URL with an unsuccessful status, e.g. an error page
+    Status(String, StatusCode),
+    /// The robots.txt of the lecture site does not allow fetching the given URL
+    Disallowed(String),
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeError::Http(err) => err.fmt(f),
+            ScrapeError::Status(url, status) => write!(f, "Fetching {} failed with status {}", url, status),
+            ScrapeError::Disallowed(url) => write!(f, "Fetching {} is disallowed by robots.txt", url),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {}
+
+impl From<reqwest::Error> for ScrapeError {
+    fn from(err: reqwest::Error) -> Self {
+        ScrapeError::Http(err)
+    }
+}
+
+/// Limits the rate of requests across all threads or tasks sharing the limiter
+pub struct RateLimiter {
+    state: Mutex<RateState>,
+}
+
+struct RateState {
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Allows up to `per_second` requests per second. A rate of zero disables the limit.
+    pub fn new(per_second: f64) -> Self {
+        let interval = if per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / per_second)
+        } else {
+            Duration::ZERO
+        };
+
+        RateLimiter {
+            state: Mutex::new(RateState { interval, next: None }),
+        }
+    }
+
+    /// Lowers the rate so that at least `interval` passes between two requests, e.g. for a crawl delay
+    pub fn slow_down(&self, interval: Duration) {
+        let mut state = self.state();
+        state.interval = state.interval.max(interval);
+    }
+
+    /// Reserves the next free slot for a request and returns how long to wait until it
+    pub fn reserve(&self) -> Duration {
+        let now = Instant::now();
+        let mut state = self.state();
+        let slot = state.next.map_or(now, |next| next.max(now));
+        state.next = Some(slot + state.interval);
+        slot - now
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, RateState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The rules of a robots.txt (RFC 9309) which apply to one user agent
+#[derive(Default, Debug)]
+pub struct RobotsTxt {
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsTxt {
+    /// A robots.txt without rules, e.g. if the site has none
+    pub fn allow_all() -> Self {
+        RobotsTxt::default()
+    }
+
+    /// Parses the rules for the given product token, e.g. `dachterasse`. Groups for other user agents
+    /// are ignored. If no group names the product token, the rules of the `*` group apply.
+    pub fn parse(content: &str, agent: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_rules = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules || groups.is_empty() {
+                        groups.push(Group::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_owned(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let (Some(group), Ok(seconds)) = (groups.last_mut(), value.parse::<f64>()) {
+                        if seconds.is_finite() && seconds >= 0.0 {
+                            group.crawl_delay = Some(Duration::from_secs_f64(seconds));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let agent = agent.to_lowercase();
+        let named = |name: &str| groups.iter().any(|group| group.agents.iter().any(|a| a == name));
+        let selected = if named(&agent) { agent } else { String::from("*") };
+
+        let mut robots = RobotsTxt::default();
+        for group in groups.into_iter().filter(|group| group.agents.contains(&selected)) {
+            robots.rules.extend(group.rules);
+            robots.crawl_delay = robots.crawl_delay.max(group.crawl_delay);
+        }
+        robots
+    }
+
+    /// The minimum time between two requests the site asks for
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+
+    /// Whether the URL may be fetched. The most specific matching rule decides, with allow rules
+    /// winning ties. URLs which no rule matches are allowed.
+    pub fn is_allowed(&self, url: &str) -> bool {
+        let path = match Url::parse(url) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_owned(),
+            },
+            Err(_) => url.to_owned(),
+        };
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| matches_pattern(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Matches a path against a robots.txt pattern, in which `*` matches any characters and a trailing `$`
+/// anchors the pattern at the end of the path. Patterns match prefixes of the path otherwise.
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let Some(rest) = parts.next().and_then(|first| path.strip_prefix(first)) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    match parts.split_last() {
+        None => !anchored || rest.is_empty(),
+        Some((last, middle)) => {
+            let mut rest = rest;
+            for part in middle {
+                match rest.find(part) {
+                    Some(position) => rest = &rest[position + part.len()..],
+                    None => return false,
+                }
+            }
+            if anchored {
+                rest.ends_with(last)
+            } else {
+                rest.contains(last)
+            }
+        }
+    }
+}
+
+/// The robots.txt of the lecture site as last fetched
+#[derive(Default)]
+pub struct RobotsCache {
+    robots: Mutex<Option<(Instant, Arc<RobotsTxt>)>>,
+}
+
+impl RobotsCache {
+    pub fn new() -> Self {
+        RobotsCache::default()
+    }
+
+    /// The cached robots.txt unless it is older than [`ROBOTS_TXT_TTL`]
+    pub fn get(&self) -> Option<Arc<RobotsTxt>> {
+        self.robots()
+            .as_ref()
+            .filter(|(fetched, _)| fetched.elapsed() < ROBOTS_TXT_TTL)
+            .map(|(_, robots)| Arc::clone(robots))
+    }
+
+    pub fn set(&self, robots: RobotsTxt) -> Arc<RobotsTxt> {
+        let robots = Arc::new(robots);
+        *self.robots() = Some((Instant::now(), Arc::clone(&robots)));
+        robots
+    }
+
+    fn robots(&self) -> std::sync::MutexGuard<'_, Option<(Instant, Arc<RobotsTxt>)>> {
+        self.robots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_most_specific_rule_of_own_group() {
+        let robots = RobotsTxt::parse(
+            "User-agent: *\n\
+             Disallow: /\n\
+             \n\
+             User-agent: Googlebot\n\
+             User-agent: Dachterasse\n\
+             Disallow: /studium/*.pdf$\n\
+             Disallow: /intern/\n\
+             Allow: /intern/lehrveranstaltungen/ # lecture pages are fine\n\
+             Crawl-delay: 2\n",
+            "dachterasse",
+        );
+
+        assert!(robots.is_allowed("https://hpi.de/studium/lehrveranstaltungen.html"));
+        assert!(!robots.is_allowed("https://hpi.de/studium/modulkatalog.pdf"));
+        assert!(robots.is_allowed("https://hpi.de/studium/modulkatalog.pdf?download=1"));
+        assert!(!robots.is_allowed("https://hpi.de/intern/"));
+        assert!(robots.is_allowed("https://hpi.de/intern/lehrveranstaltungen/dbs1.html"));
+        assert_eq!(robots.crawl_delay(), Some(Duration::from_secs(2)));
+
+        let others = RobotsTxt::parse("User-agent: *\nDisallow: /\n", "dachterasse");
+        assert!(!others.is_allowed("https://hpi.de/studium/lehrveranstaltungen.html"));
+        assert!(others.is_allowed("https://hpi.de/robots.txt"));
+    }
+
+    #[test]
+    fn spaces_reserved_slots() {
+        let limiter = RateLimiter::new(10.0);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(limiter.reserve() > Duration::from_millis(90));
+
+        let unlimited = RateLimiter::new(0.0);
+        assert_eq!(unlimited.reserve(), Duration::ZERO);
+        assert_eq!(unlimited.reserve(), Duration::ZERO);
+    }
+}
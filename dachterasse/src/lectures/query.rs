@@ -0,0 +1,296 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::{Not, RangeInclusive};
+
+use regex::Regex;
+
+use super::entities::{Degrees, Language, Lecture, StaticDegree};
+
+/// A composable predicate on lectures.
+///
+/// Filters can be combined using [`Filter::and`], [`Filter::or`] and the `!` operator:
+///
+/// ```
+/// use dachterasse::query::Filter;
+///
+/// let filter = Filter::module("Softwarebasissysteme")
+///     .and(Filter::ects(6..=9))
+///     .and(!Filter::title("Seminar"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub enum Filter {
+    /// Matches every lecture
+    #[default]
+    All,
+    /// Matches lectures which can be taken in the given module
+    Module(String),
+    /// Matches lectures which can be taken in the given category, optionally only within the given module
+    Category {
+        module: Option<String>,
+        category: String,
+    },
+    /// Matches lectures whose title contains the given text, ignoring case
+    Title(String),
+    /// Matches lectures whose title matches the given regular expression
+    TitlePattern(Regex),
+    /// Matches lectures in which one of the lecturers' names contains the given text, ignoring case
+    Lecturer(String),
+    /// Matches lectures whose ECTS are within the given range
+    Ects(RangeInclusive<u8>),
+    Language(Language),
+    /// Matches lectures whose semester contains the given text, ignoring case
+    Semester(String),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn module(module: &str) -> Self {
+        Filter::Module(module.to_owned())
+    }
+
+    /// Matches lectures which can be taken in any of the given modules
+    pub fn any_module<'m>(modules: impl IntoIterator<Item = &'m str>) -> Self {
+        Filter::Or(modules.into_iter().map(Filter::module).collect())
+    }
+
+    pub fn category(category: &str) -> Self {
+        Filter::Category {
+            module: None,
+            category: category.to_owned(),
+        }
+    }
+
+    pub fn category_in(module: &str, category: &str) -> Self {
+        Filter::Category {
+            module: Some(module.to_owned()),
+            category: category.to_owned(),
+        }
+    }
+
+    /// Matches lectures which can be taken in any of the given categories within any of the given modules.
+    /// An empty list of modules or categories matches every module or category respectively.
+    pub fn placed_in(modules: &[impl AsRef<str>], categories: &[impl AsRef<str>]) -> Self {
+        match (modules.is_empty(), categories.is_empty()) {
+            (true, true) => Filter::All,
+            (false, true) => Filter::any_module(modules.iter().map(AsRef::as_ref)),
+            (true, false) => Filter::Or(categories.iter().map(|c| Filter::category(c.as_ref())).collect()),
+            (false, false) => Filter::Or(
+                modules
+                    .iter()
+                    .flat_map(|m| categories.iter().map(move |c| Filter::category_in(m.as_ref(), c.as_ref())))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn title(text: &str) -> Self {
+        Filter::Title(text.to_owned())
+    }
+
+    pub fn title_matches(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Filter::TitlePattern(Regex::new(pattern)?))
+    }
+
+    pub fn lecturer(name: &str) -> Self {
+        Filter::Lecturer(name.to_owned())
+    }
+
+    pub fn ects(range: RangeInclusive<u8>) -> Self {
+        Filter::Ects(range)
+    }
+
+    pub fn language(language: Language) -> Self {
+        Filter::Language(language)
+    }
+
+    pub fn semester(semester: &str) -> Self {
+        Filter::Semester(semester.to_owned())
+    }
+
+    /// Combines two filters so that both have to match
+    pub fn and(self, other: Filter) -> Self {
+        match (self, other) {
+            (Filter::All, other) | (other, Filter::All) => other,
+            (Filter::And(mut filters), other) => {
+                filters.push(other);
+                Filter::And(filters)
+            }
+            (filter, other) => Filter::And(vec![filter, other]),
+        }
+    }
+
+    /// Combines two filters so that at least one of them has to match
+    pub fn or(self, other: Filter) -> Self {
+        match (self, other) {
+            (Filter::Or(mut filters), other) => {
+                filters.push(other);
+                Filter::Or(filters)
+            }
+            (filter, other) => Filter::Or(vec![filter, other]),
+        }
+    }
+
+    pub fn matches(&self, lecture: &Lecture) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Module(module) => modules(lecture).any(|(name, _)| name.eq_ignore_ascii_case(module)),
+            Filter::Category { module, category } => modules(lecture)
+                .filter(|(name, _)| module.as_ref().is_none_or(|module| name.eq_ignore_ascii_case(module)))
+                .any(|(_, categories)| categories.iter().any(|c| c.eq_ignore_ascii_case(category))),
+            Filter::Title(text) => contains_ignore_case(&lecture.title, text),
+            Filter::TitlePattern(pattern) => pattern.is_match(&lecture.title),
+            Filter::Lecturer(name) => lecture.lecturers.iter().any(|l| contains_ignore_case(l, name)),
+            Filter::Ects(range) => lecture.ects.is_some_and(|ects| range.contains(&ects)),
+            Filter::Language(language) => lecture.language.as_ref() == Some(language),
+            Filter::Semester(semester) => lecture
+                .semester
+                .as_ref()
+                .is_some_and(|s| contains_ignore_case(s, semester)),
+            Filter::And(filters) => filters.iter().all(|filter| filter.matches(lecture)),
+            Filter::Or(filters) => filters.iter().any(|filter| filter.matches(lecture)),
+            Filter::Not(filter) => !filter.matches(lecture),
+        }
+    }
+}
+
+impl Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Filter::Not(filter) => *filter,
+            filter => Filter::Not(Box::new(filter)),
+        }
+    }
+}
+
+fn modules(lecture: &Lecture) -> impl Iterator<Item = (&String, &Vec<String>)> {
+    lecture.categories.iter().flatten()
+}
+
+fn contains_ignore_case(text: &str, part: &str) -> bool {
+    text.to_lowercase().contains(&part.to_lowercase())
+}
+
+/// A query for lectures across one or more degrees
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    degrees: Vec<&'static StaticDegree>,
+    filter: Filter,
+}
+
+impl Query {
+    /// Creates a query which matches all lectures of all degrees
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Restricts the query to the given degree. Can be called multiple times to query several degrees.
+    pub fn degree(mut self, degree: &'static StaticDegree) -> Self {
+        self.degrees.push(degree);
+        self
+    }
+
+    pub fn degrees(mut self, degrees: impl IntoIterator<Item = &'static StaticDegree>) -> Self {
+        self.degrees.extend(degrees);
+        self
+    }
+
+    /// Adds a filter which all results have to match
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = std::mem::take(&mut self.filter).and(filter);
+        self
+    }
+
+    /// Runs the query against lectures grouped by degree.
+    /// Results are ordered by degree as given in the query or in `Degrees::all()` if no degree was given.
+    pub fn run<'a>(&self, lectures: &'a HashMap<&'static StaticDegree, Vec<Lecture>>) -> QueryResult<'a> {
+        let degrees = if self.degrees.is_empty() {
+            Degrees::all().iter().collect()
+        } else {
+            self.degrees.clone()
+        };
+        let filter = self.filter.clone();
+
+        QueryResult {
+            matches: Box::new(
+                degrees
+                    .into_iter()
+                    .filter_map(move |degree| Some((degree, lectures.get(degree)?)))
+                    .flat_map(|(degree, lectures)| lectures.iter().map(move |lecture| QueryMatch { degree, lecture }))
+                    .filter(move |m| filter.matches(m.lecture)),
+            ),
+        }
+    }
+}
+
+/// A lecture found by a query together with the degree in which it was found
+#[derive(Clone, Copy)]
+pub struct QueryMatch<'a> {
+    pub degree: &'static StaticDegree,
+    pub lecture: &'a Lecture,
+}
+
+/// Iterator over all lectures matching a query
+pub struct QueryResult<'a> {
+    matches: Box<dyn Iterator<Item = QueryMatch<'a>> + 'a>,
+}
+
+impl<'a> QueryResult<'a> {
+    /// Collects the matching lectures, listing lectures which were found in several degrees only once
+    pub fn lectures(self) -> Vec<&'a Lecture> {
+        let mut seen = HashSet::new();
+        self.map(|m| m.lecture).filter(|lecture| seen.insert(&lecture.url)).collect()
+    }
+}
+
+impl<'a> Iterator for QueryResult<'a> {
+    type Item = QueryMatch<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.matches.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lecture(title: &str, module: &str, category: &str, ects: u8) -> Lecture {
+        Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/{}.html", title),
+            categories: Some(HashMap::from([(module.to_owned(), vec![category.to_owned()])])),
+            ects: Some(ects),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn combines_filters() {
+        let lectures = HashMap::from([(
+            &Degrees::ITSE_BA,
+            vec![
+                lecture("Datenbanksysteme I", "Softwarebasissysteme", "Datenbanken", 6),
+                lecture("Datenbanksysteme II", "Vertiefungsgebiete", "Datenbanken", 6),
+                lecture("Compilerbau", "Vertiefungsgebiete", "Programmiersprachen", 3),
+            ],
+        )]);
+
+        let titles = |query: Query| query.run(&lectures).map(|m| m.lecture.title.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            titles(Query::new().filter(Filter::category_in("vertiefungsgebiete", "Datenbanken"))),
+            vec!["Datenbanksysteme II"]
+        );
+        assert_eq!(
+            titles(Query::new().filter(Filter::ects(4..=9).and(!Filter::title("ii")))),
+            vec!["Datenbanksysteme I"]
+        );
+        assert_eq!(
+            titles(Query::new().degree(&Degrees::DE_MA).filter(Filter::title("Daten"))),
+            Vec::<&str>::new()
+        );
+    }
+}
@@ -14,8 +14,8 @@ impl<'a> LectureRepository<'a> {
     /// Create a new repository which serves lectures from its specified data sources
     ///
     /// * `sources` - Data sources from where the repository tries to serve the lecture data.
-    /// Loading the data will be attempted in the order in which data sources appear in the vector
-    /// until one data source returns a successful result.
+    ///   Loading the data will be attempted in the order in which data sources appear in the vector
+    ///   until one data source returns a successful result.
     pub fn new() -> Self {
         LectureRepository {
             sources: Vec::new(),
@@ -47,33 +47,117 @@ impl<'a> LectureRepository<'a> {
         self
     }
 
-    /// Load lectures from repository data sources and write them to read-write sources
+    /// Load lectures from repository data sources and write them to the read-write sources which did not
+    /// return them, i.e. the ones which were tried before. The source which returned the lectures is not
+    /// written to, so that the age of its lectures is kept. Sources which fail to save the lectures are
+    /// skipped, since the lectures were loaded anyway.
     pub fn synchronized_load(&mut self, degree: &'static StaticDegree) -> Result<Vec<Lecture>, Error> {
-        match self.try_loading(degree) {
-            Some(lectures) => {
-                for rw in &mut self.sources {
-                    let _ = rw.save_lectures(degree, &lectures);
-                }
-                Ok(lectures)
+        let (lectures, loaded_from) = self
+            .try_loading(degree)
+            .ok_or_else(|| format!("No source returned lectures for degree {}", degree.name))?;
+
+        for rw in &mut self.sources[..loaded_from] {
+            if let Err(e) = rw.save_lectures(degree, &lectures) {
+                eprintln!("Error saving lecture to some datasource with error: {}", e);
             }
-            None => Err(format!(
-                "No source returned lectures for degree {}",
-                degree.name
-            )),
         }
+        Ok(lectures)
     }
 
-    fn try_loading(&self, degree: &'static StaticDegree) -> Option<Vec<Lecture>> {
-        self.sources
+    /// Load lectures from the read-only sources only and write them to all read-write sources.
+    /// Used to replace outdated lectures, e.g. in a cache, with up-to-date ones.
+    pub fn refresh(&mut self, degree: &'static StaticDegree) -> Result<Vec<Lecture>, Error> {
+        let lectures = self
+            .read_only_sources
             .iter()
             .find_map(|source| source.load_lectures(degree).ok())
-            .map_or_else(
-                || {
-                    self.read_only_sources
-                        .iter()
-                        .find_map(|source| source.load_lectures(degree).ok())
-                },
-                Some,
-            )
+            .ok_or_else(|| format!("No read-only source returned lectures for degree {}", degree.name))?;
+
+        for rw in &mut self.sources {
+            rw.save_lectures(degree, &lectures)?;
+        }
+        Ok(lectures)
+    }
+
+    /// Loads lectures from the first source which returns them, together with the index of that source in
+    /// `sources`. Lectures from read-only sources have the index `sources.len()`.
+    fn try_loading(&self, degree: &'static StaticDegree) -> Option<(Vec<Lecture>, usize)> {
+        self.sources
+            .iter()
+            .enumerate()
+            .find_map(|(index, source)| Some((source.load_lectures(degree).ok()?, index)))
+            .or_else(|| {
+                self.read_only_sources
+                    .iter()
+                    .find_map(|source| source.load_lectures(degree).ok())
+                    .map(|lectures| (lectures, self.sources.len()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::datasource::{LoadResult, ReadOnlyDataSource, SaveResult};
+    use crate::sources::{FSDataSource, InMemoryDataSource};
+    use crate::Degrees;
+
+    /// A cache which can neither be read nor written, e.g. in a directory without permissions
+    struct BrokenCache;
+
+    impl ReadOnlyDataSource for BrokenCache {
+        fn load_lectures(&self, _degree: &'static StaticDegree) -> LoadResult {
+            Err(String::from("Permission denied"))
+        }
+    }
+
+    impl ReadWriteDataSource for BrokenCache {
+        fn save_lectures(&mut self, _degree: &'static StaticDegree, _lectures: &[Lecture]) -> SaveResult {
+            Err(String::from("Permission denied"))
+        }
+    }
+
+    #[test]
+    fn serves_lectures_which_could_not_be_cached() {
+        let lectures = vec![Lecture {
+            title: String::from("Compilerbau"),
+            url: String::from("https://hpi.de/studium/compilerbau.html"),
+            ..Default::default()
+        }];
+        let mut source = InMemoryDataSource::new();
+        source.save_lectures(&Degrees::ITSE_BA, &lectures).unwrap();
+
+        let mut repository = LectureRepository::new().source(BrokenCache).source(source);
+        assert_eq!(repository.synchronized_load(&Degrees::ITSE_BA).unwrap(), lectures);
+    }
+
+    #[test]
+    fn reading_the_cache_keeps_its_age() {
+        let dir = std::env::temp_dir().join(format!("dachterasse-repository-{}", std::process::id()));
+        let cache_dir = dir.to_string_lossy().into_owned();
+        let lectures = vec![Lecture {
+            title: String::from("Compilerbau"),
+            url: String::from("https://hpi.de/studium/compilerbau.html"),
+            ..Default::default()
+        }];
+        let mut cache = FSDataSource::new(cache_dir.clone());
+        cache.save_lectures(&Degrees::ITSE_BA, &lectures).unwrap();
+        File::options()
+            .write(true)
+            .open(dir.join("itse-ba.json"))
+            .and_then(|file| file.set_modified(SystemTime::now() - Duration::from_secs(2 * 60 * 60)))
+            .unwrap();
+
+        let mut repository = LectureRepository::new()
+            .source(InMemoryDataSource::new())
+            .source(FSDataSource::new(cache_dir));
+        assert_eq!(repository.synchronized_load(&Degrees::ITSE_BA).unwrap(), lectures);
+        assert_eq!(repository.synchronized_load(&Degrees::ITSE_BA).unwrap(), lectures);
+
+        assert!(cache.is_stale(&Degrees::ITSE_BA, Duration::from_secs(60 * 60)));
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
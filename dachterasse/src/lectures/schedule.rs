@@ -0,0 +1,409 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, Weekday};
+use regex::{Captures, Regex};
+
+use super::entities::Lecture;
+
+/// The dates on which a lecture takes place
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Schedule {
+    /// Sessions taking place every week during the lecture period
+    #[serde(default)]
+    pub sessions: Vec<Session>,
+    /// Sessions taking place only once, e.g. exams or block seminars
+    #[serde(default)]
+    pub dates: Vec<Appointment>,
+}
+
+impl Schedule {
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.dates.is_empty()
+    }
+}
+
+/// The period of a semester in which lectures take place
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LecturePeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl LecturePeriod {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        LecturePeriod { start, end }
+    }
+
+    /// The approximate lecture period of the winter semester starting in the given year (mid-October to mid-February)
+    pub fn winter(year: i32) -> Self {
+        LecturePeriod::new(date(year, 10, 15), date(year + 1, 2, 15))
+    }
+
+    /// The approximate lecture period of the summer semester in the given year (mid-April to the end of July)
+    pub fn summer(year: i32) -> Self {
+        LecturePeriod::new(date(year, 4, 15), date(year, 7, 31))
+    }
+
+    /// The lecture period of the current or upcoming semester
+    pub fn current() -> Self {
+        Self::containing(Local::now().date_naive())
+    }
+
+    /// The lecture period of the semester which the given date belongs to
+    pub fn containing(day: NaiveDate) -> Self {
+        match day.month() {
+            1..=3 => Self::winter(day.year() - 1),
+            4..=9 => Self::summer(day.year()),
+            _ => Self::winter(day.year()),
+        }
+    }
+
+    /// Parses semester names as found on lecture pages, e.g. "Wintersemester 2022/23" or "Sommersemester 2023"
+    pub fn from_semester(semester: &str) -> Option<Self> {
+        static SEMESTER: OnceLock<Regex> = OnceLock::new();
+        let captures = SEMESTER
+            .get_or_init(|| Regex::new(r"(?i)(winter|sommer|summer)\w*\s*(\d{4})").unwrap())
+            .captures(semester)?;
+        let year = captures[2].parse().ok()?;
+        if captures[1].eq_ignore_ascii_case("winter") {
+            Some(Self::winter(year))
+        } else {
+            Some(Self::summer(year))
+        }
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&day)
+    }
+
+    fn overlaps(&self, other: &LecturePeriod) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The first date within this period which falls on the given weekday
+    pub fn first(&self, weekday: Weekday) -> NaiveDate {
+        let offset = (7 + weekday.num_days_from_monday() - self.start.weekday().num_days_from_monday()) % 7;
+        self.start + chrono::Duration::days(i64::from(offset))
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// A weekly recurring session
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Session {
+    pub weekday: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub room: Option<String>,
+}
+
+/// A session taking place on a single date
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Appointment {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub room: Option<String>,
+}
+
+const TIME_RANGE: &str = r"(\d{1,2})[:.](\d{2})\s*(?:Uhr)?\s*[-–]\s*(\d{1,2})[:.](\d{2})\s*(?:Uhr)?";
+
+/// Parses schedule entries like "Montag, 09:15 - 10:45 Uhr, HS 1" or "14.02.2023, 13:30-15:00, HS 2"
+pub fn parse_schedule<'a>(lines: impl IntoIterator<Item = &'a str>) -> Schedule {
+    static REGEXES: OnceLock<(Regex, Regex)> = OnceLock::new();
+    let (session_regex, date_regex) = REGEXES.get_or_init(|| {
+        let weekday = r"(?i)\b(Montag|Dienstag|Mittwoch|Donnerstag|Freitag|Samstag|Sonntag|Monday|Tuesday|Wednesday|Thursday|Friday|Saturday|Sunday|Mo|Di|Mi|Do|Fr|Sa|So|Mon|Tue|Wed|Thu|Fri|Sat|Sun)\b\.?,?\s*";
+        (
+            Regex::new(&format!("{}{}(.*)", weekday, TIME_RANGE)).unwrap(),
+            Regex::new(&format!(r"(\d{{1,2}})\.(\d{{1,2}})\.(\d{{4}})\s*,?\s*(?:{})?{}(.*)", weekday, TIME_RANGE)).unwrap(),
+        )
+    });
+
+    let mut schedule = Schedule::default();
+    for line in lines {
+        if let Some(captures) = date_regex.captures(line) {
+            let date = NaiveDate::from_ymd_opt(number(&captures, 3), number(&captures, 2), number(&captures, 1));
+            if let (Some(date), Some((start, end))) = (date, times(&captures, 5)) {
+                schedule.dates.push(Appointment {
+                    date,
+                    start,
+                    end,
+                    room: room(&captures[9]),
+                });
+            }
+        } else if let Some(captures) = session_regex.captures(line) {
+            if let (Some(weekday), Some((start, end))) = (parse_weekday(&captures[1]), times(&captures, 2)) {
+                schedule.sessions.push(Session {
+                    weekday,
+                    start,
+                    end,
+                    room: room(&captures[6]),
+                });
+            }
+        }
+    }
+    schedule
+}
+
+fn number<T: std::str::FromStr + Default>(captures: &Captures, group: usize) -> T {
+    captures[group].parse().unwrap_or_default()
+}
+
+/// Parses a time range starting at the given capture group
+fn times(captures: &Captures, group: usize) -> Option<(NaiveTime, NaiveTime)> {
+    let start = NaiveTime::from_hms_opt(number(captures, group), number(captures, group + 1), 0)?;
+    let end = NaiveTime::from_hms_opt(number(captures, group + 2), number(captures, group + 3), 0)?;
+    (start < end).then_some((start, end))
+}
+
+fn room(text: &str) -> Option<String> {
+    let room = text.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | '|' | '-' | '–'));
+    let room = ["Raum", "Room", "in", "im"]
+        .iter()
+        .find_map(|prefix| room.strip_prefix(prefix).filter(|rest| rest.starts_with([' ', ':'])))
+        .unwrap_or(room)
+        .trim_start_matches([' ', ':'])
+        .trim();
+    (!room.is_empty()).then(|| room.to_owned())
+}
+
+fn parse_weekday(name: &str) -> Option<Weekday> {
+    let weekday = match name.to_lowercase().get(..2)? {
+        "mo" => Weekday::Mon,
+        "di" | "tu" => Weekday::Tue,
+        "mi" | "we" => Weekday::Wed,
+        "do" | "th" => Weekday::Thu,
+        "fr" => Weekday::Fri,
+        "sa" => Weekday::Sat,
+        "so" | "su" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+/// A time slot in which two lectures take place at the same time
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum Overlap {
+    Weekly {
+        weekday: Weekday,
+        start: NaiveTime,
+        end: NaiveTime,
+    },
+    Once {
+        date: NaiveDate,
+        start: NaiveTime,
+        end: NaiveTime,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Conflict<'a> {
+    pub first: &'a Lecture,
+    pub second: &'a Lecture,
+    pub overlap: Overlap,
+}
+
+/// A set of lectures which a student wants to attend together
+pub struct Timetable<'a> {
+    lectures: Vec<&'a Lecture>,
+}
+
+impl<'a> Timetable<'a> {
+    /// Creates a timetable of the given lectures. Lectures which are given several times are included once.
+    pub fn new(lectures: impl IntoIterator<Item = &'a Lecture>) -> Self {
+        let mut urls = HashSet::new();
+        Timetable {
+            lectures: lectures.into_iter().filter(|lecture| urls.insert(&lecture.url)).collect(),
+        }
+    }
+
+    pub fn lectures(&self) -> &[&'a Lecture] {
+        &self.lectures
+    }
+
+    /// Returns all pairs of lectures whose sessions overlap.
+    /// Lectures without a known schedule never conflict with other lectures. Weekly sessions only take place
+    /// during the lecture period of the lecture's semester or, if the semester is unknown, in every semester.
+    pub fn conflicts(&self) -> Vec<Conflict<'a>> {
+        let mut conflicts = Vec::new();
+        for (index, first) in self.lectures.iter().enumerate() {
+            for second in &self.lectures[index + 1..] {
+                if let (Some(a), Some(b)) = (&first.schedule, &second.schedule) {
+                    let a = (a, lecture_period(first));
+                    let b = (b, lecture_period(second));
+                    conflicts.extend(overlaps(a, b).into_iter().map(|overlap| Conflict {
+                        first,
+                        second,
+                        overlap,
+                    }));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+fn lecture_period(lecture: &Lecture) -> Option<LecturePeriod> {
+    lecture.semester.as_deref().and_then(LecturePeriod::from_semester)
+}
+
+/// Whether a weekly session during the given lecture period takes place on the given date
+fn takes_place(session: &Session, period: Option<LecturePeriod>, date: NaiveDate) -> bool {
+    session.weekday == date.weekday() && period.is_none_or(|period| period.contains(date))
+}
+
+fn overlaps(
+    (a, a_period): (&Schedule, Option<LecturePeriod>),
+    (b, b_period): (&Schedule, Option<LecturePeriod>),
+) -> Vec<Overlap> {
+    let mut result = Vec::new();
+
+    let concurrent = match (a_period, b_period) {
+        (Some(a_period), Some(b_period)) => a_period.overlaps(&b_period),
+        _ => true,
+    };
+    for x in a.sessions.iter().filter(|_| concurrent) {
+        for y in b.sessions.iter().filter(|y| y.weekday == x.weekday) {
+            if let Some((start, end)) = intersect((x.start, x.end), (y.start, y.end)) {
+                result.push(Overlap::Weekly {
+                    weekday: x.weekday,
+                    start,
+                    end,
+                });
+            }
+        }
+    }
+
+    let once = |date: NaiveDate, times: Option<(NaiveTime, NaiveTime)>| times.map(|(start, end)| Overlap::Once { date, start, end });
+    for x in &a.dates {
+        for y in b.dates.iter().filter(|y| y.date == x.date) {
+            result.extend(once(x.date, intersect((x.start, x.end), (y.start, y.end))));
+        }
+        for y in b.sessions.iter().filter(|y| takes_place(y, b_period, x.date)) {
+            result.extend(once(x.date, intersect((x.start, x.end), (y.start, y.end))));
+        }
+    }
+    for y in &b.dates {
+        for x in a.sessions.iter().filter(|x| takes_place(x, a_period, y.date)) {
+            result.extend(once(y.date, intersect((x.start, x.end), (y.start, y.end))));
+        }
+    }
+
+    result
+}
+
+fn intersect(a: (NaiveTime, NaiveTime), b: (NaiveTime, NaiveTime)) -> Option<(NaiveTime, NaiveTime)> {
+    let start = a.0.max(b.0);
+    let end = a.1.min(b.1);
+    (start < end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_sessions_and_dates() {
+        let schedule = parse_schedule([
+            "Montag, 09:15 - 10:45 Uhr, HS 1",
+            "Thu 13:30-15:00 Room A-1.1",
+            "14.02.2023, 13:30 - 15:00 Uhr, Raum: HS 2",
+            "Die Vorlesung beginnt am Montag.",
+        ]);
+
+        assert_eq!(
+            schedule.sessions,
+            vec![
+                Session {
+                    weekday: Weekday::Mon,
+                    start: time(9, 15),
+                    end: time(10, 45),
+                    room: Some(String::from("HS 1")),
+                },
+                Session {
+                    weekday: Weekday::Thu,
+                    start: time(13, 30),
+                    end: time(15, 0),
+                    room: Some(String::from("A-1.1")),
+                },
+            ]
+        );
+        assert_eq!(
+            schedule.dates,
+            vec![Appointment {
+                date: NaiveDate::from_ymd_opt(2023, 2, 14).unwrap(),
+                start: time(13, 30),
+                end: time(15, 0),
+                room: Some(String::from("HS 2")),
+            }]
+        );
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        let lecture = |title: &str, semester: &str, lines: &[&str]| Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/{}.html", title),
+            semester: Some(semester.to_owned()),
+            schedule: Some(parse_schedule(lines.iter().copied())),
+            ..Default::default()
+        };
+        let winter = "Wintersemester 2022/23";
+        let lectures = [
+            lecture("a", winter, &["Montag, 09:15 - 10:45 Uhr"]),
+            lecture("b", winter, &["Montag, 10:00 - 11:30 Uhr"]),
+            lecture("c", winter, &["Montag, 11:00 - 12:30 Uhr", "13.02.2023, 09:00 - 10:00 Uhr"]),
+            lecture("d", "Sommersemester 2023", &["Montag, 09:00 - 10:00 Uhr", "17.04.2023, 09:00 - 10:00 Uhr"]),
+        ];
+
+        let conflicts = Timetable::new(lectures.iter().chain([&lectures[0]])).conflicts();
+        let pairs = conflicts
+            .iter()
+            .map(|c| (c.first.title.as_str(), c.second.title.as_str(), c.overlap.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    "a",
+                    "b",
+                    Overlap::Weekly {
+                        weekday: Weekday::Mon,
+                        start: time(10, 0),
+                        end: time(10, 45)
+                    }
+                ),
+                (
+                    "a",
+                    "c",
+                    Overlap::Once {
+                        date: NaiveDate::from_ymd_opt(2023, 2, 13).unwrap(),
+                        start: time(9, 15),
+                        end: time(10, 0)
+                    }
+                ),
+                (
+                    "b",
+                    "c",
+                    Overlap::Weekly {
+                        weekday: Weekday::Mon,
+                        start: time(11, 0),
+                        end: time(11, 30)
+                    }
+                ),
+            ]
+        );
+    }
+}
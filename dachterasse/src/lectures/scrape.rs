@@ -1,103 +1,199 @@
-use std::collections::HashMap;
-use regex::Regex;
-use reqwest::blocking::Client;
-use scraper::{Html, Selector};
-use scraper::element_ref::Text;
+use std::sync::Arc;
+use std::thread;
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
 use crate::lectures::entities::StaticDegree;
+use super::config::Config;
 use super::entities::Lecture;
+use super::pages::CachedPage;
+use super::politeness::{RobotsTxt, ScrapeError};
+use super::site::{self, Site};
 
-#[derive(Default)]
 pub struct LectureScraper {
-    client: Client
+    client: Client,
+    site: Site,
 }
 
-pub type Error = reqwest::Error;
+pub type Error = ScrapeError;
+
+impl Default for LectureScraper {
+    fn default() -> Self {
+        LectureScraper::new()
+    }
+}
 
 impl LectureScraper {
     pub fn new() -> Self {
-        LectureScraper { client: Client::new() }
+        LectureScraper::from_config(&Config::default())
     }
 
-    // TODO: Extract document fetching and parsing into separate method for better testability
+    /// Creates a scraper using the base URL, user agent, timeout, concurrency and politeness settings of the config.
+    /// If a cache path is configured, validators of scraped pages are kept there for conditional requests.
+    pub fn from_config(config: &Config) -> Self {
+        let client = Client::builder()
+            .user_agent(config.user_agent_header())
+            .timeout(config.timeout)
+            .build()
+            .expect("Could not initialize HTTP client");
+
+        LectureScraper {
+            client,
+            site: Site::from_config(config),
+        }
+    }
+
+    /// Fetches the lectures listed on the overview page of a degree, without details. If the page did not
+    /// change since it was last fetched, the previously parsed lectures are returned.
     pub fn fetch_lectures(&self, degree: &StaticDegree) -> Result<Vec<Lecture>, Error> {
-        let url = degree.url;
-        let document = get_text(url, &self.client)?;
-        let fragment = Html::parse_document(&document);
-        let selector = Selector::parse("a.courselink").unwrap();
-        let link_regex = Regex::new(r"/studium.*\.html").unwrap();
-
-        Ok(fragment.select(&selector)
-            .map(|element| {
-                Lecture {
-                    title: clean(element.text()),
-                    url: String::from("https://hpi.de") + &link_regex.captures(&*element.html()).unwrap()[0],
-                    categories: None
-                }
-            })
-            .collect())
+        let url = self.site.overview_url(degree);
+        if !self.robots()?.is_allowed(&url) {
+            return Err(ScrapeError::Disallowed(url));
+        }
+        let cached = self.site.pages.overview(&url);
+        let response = self.send(
+            self.client
+                .get(&url)
+                .headers(cached.as_ref().map(CachedPage::conditions).unwrap_or_default()),
+        )?;
+
+        if let Some(lectures) = site::unchanged(&url, response.status(), cached)? {
+            return Ok(lectures);
+        }
+        let headers = response.headers().clone();
+        Ok(self.site.set_overview(&url, &headers, &response.text()?))
     }
 
-    // TODO: Extract document fetching and parsing into separate method for better testability
+    /// Fetches all lectures of a degree including their details. Lecture pages are fetched by up to
+    /// `concurrency` threads at the same time.
     pub fn fetch_lecture_details(&self, degree: &StaticDegree) -> Result<Vec<Lecture>, Error> {
         let mut lectures = self.fetch_lectures(degree)?;
-        for mut lecture in &mut lectures {
-            // TODO: Do asynchronously
-            let document = get_text(&lecture.url, &self.client)?;
-
-            if let Some(inner_fragment) = self.scrape_modules(document.as_str(), degree.name) {
-                let module_list = Html::parse_fragment(inner_fragment.as_str());
-                let item_selector = Selector::parse("li").unwrap();
-                let categories: Vec<(String, Vec<String>)> = module_list.select(&item_selector)
-                    .map(|element| {
-                        (clean(element.text()),
-                         Html::parse_fragment(element.inner_html().as_str())
-                             .select(&item_selector)
-                             .map(|child| clean(child.text()))
-                             .collect::<Vec<_>>())
+        let chunk_size = lectures.len().div_ceil(self.site.concurrency).max(1);
+
+        thread::scope(|scope| {
+            let workers = lectures
+                .chunks_mut(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        for lecture in chunk {
+                            self.fetch_details(degree, lecture)?;
+                        }
+                        Ok::<_, Error>(())
                     })
-                    .filter(|(_, children)| !children.is_empty())
-                    .collect();
+                })
+                .collect::<Vec<_>>();
 
-                let mut category_map = HashMap::<String, Vec<String>>::new();
-                for mut category in categories {
-                    category_map.entry(category.0).or_insert(Vec::new()).append(&mut category.1);
-                }
-                lecture.categories = Some(category_map);
-            }
-        }
+            workers
+                .into_iter()
+                .try_for_each(|worker| worker.join().expect("Scraper thread panicked"))
+        })?;
 
+        self.site.save_pages();
         Ok(lectures)
     }
 
-    fn scrape_modules(&self, document: &str, degree: &str) -> Option<String> {
-        let fragment = Html::parse_document(document);
-        let selector = Selector::parse("div.tx_dscclipclap").unwrap();
-        let header_selector = Selector::parse("div.tx_dscclipclap_header").unwrap();
-
-        fragment.select(&selector)
-            .find(|element| {
-                Html::parse_fragment(element.inner_html().as_str())
-                    .select(&header_selector)
-                    .any(|inner| clean(inner.text()) == degree)
-            })
-            .map(|optional| optional.inner_html())
+    /// Fetches the details of a lecture, which are only parsed again if its page changed.
+    /// Lectures whose page is disallowed by robots.txt are left without details.
+    fn fetch_details(&self, degree: &StaticDegree, lecture: &mut Lecture) -> Result<(), Error> {
+        if !self.robots()?.is_allowed(&lecture.url) {
+            return Ok(());
+        }
+        let cached = self.site.pages.details(&lecture.url, degree.name);
+        let response = self.send(
+            self.client
+                .get(&lecture.url)
+                .headers(cached.as_ref().map(CachedPage::conditions).unwrap_or_default()),
+        )?;
+
+        let details = match site::unchanged(&lecture.url, response.status(), cached)? {
+            Some(details) => details,
+            None => {
+                let headers = response.headers().clone();
+                self.site.set_details(&lecture.url, degree, &headers, &response.text()?)
+            }
+        };
+        details.apply_to(lecture);
+        Ok(())
     }
-}
 
-fn clean(text: Text) -> String {
-    String::from(clean_str(text))
-}
+    /// The site's robots.txt, which is fetched if it is not cached yet
+    fn robots(&self) -> Result<Arc<RobotsTxt>, Error> {
+        let url = match self.site.robots() {
+            Ok(robots) => return Ok(robots),
+            Err(url) => url,
+        };
+
+        let response = self.send(self.client.get(&url))?;
+        let document = if self.site.has_robots(&url, response.status())? {
+            Some(response.text()?)
+        } else {
+            None
+        };
+        Ok(self.site.set_robots(document.as_deref()))
+    }
 
-fn clean_str(text: Text) -> &str {
-    text.collect::<Vec<_>>()
-        .first()
-        .unwrap()
-        .trim()
+    /// Sends the request once the rate limit allows it
+    fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        thread::sleep(self.site.limiter.reserve());
+        Ok(request.send()?)
+    }
 }
 
-fn get_text(url: &str, client: &Client) -> Result<String, reqwest::Error> {
-    client
-        .get(url)
-        .send()?
-        .text()
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::lectures::entities::Degrees;
+
+    /// Serves the given responses to one request each, in order
+    fn serve(responses: Vec<(&'static str, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let response = format!(
+                    "HTTP/1.1 {}\r\nETag: \"{}\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    status.len(),
+                    body.len(),
+                    body
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn error_pages_leave_cached_pages_unchanged() {
+        let overview = r#"<a class="courselink" href="/studium/dbs1.html">Datenbanksysteme I</a>"#;
+        let config = Config {
+            base_url: serve(vec![("200 OK", overview), ("503 Service Unavailable", "<p>Maintenance</p>")]),
+            respect_robots_txt: false,
+            rate_limit: 0.0,
+            ..Config::default()
+        };
+        let scraper = LectureScraper::from_config(&config);
+        let degree = &Degrees::ITSE_BA;
+
+        assert_eq!(scraper.fetch_lectures(degree).unwrap().len(), 1);
+        let cached = scraper.site.pages.overview(&scraper.site.overview_url(degree)).unwrap();
+
+        match scraper.fetch_lectures(degree) {
+            Err(ScrapeError::Status(_, status)) => assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE),
+            other => panic!("expected a status error, got {:?}", other),
+        }
+        let after = scraper.site.pages.overview(&scraper.site.overview_url(degree)).unwrap();
+        assert_eq!(after.etag, cached.etag);
+        assert_eq!(after.content.len(), 1);
+    }
 }
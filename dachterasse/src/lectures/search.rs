@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use super::entities::Lecture;
+
+/// The part of a lecture in which a search term was found
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Field {
+    Title,
+    Description,
+    Modules,
+}
+
+impl Field {
+    const ALL: [Field; 3] = [Field::Title, Field::Description, Field::Modules];
+
+    fn weight(&self) -> f32 {
+        match self {
+            Field::Title => 3.0,
+            Field::Description => 1.0,
+            Field::Modules => 1.5,
+        }
+    }
+
+    /// Returns the searchable text of a lecture for this field
+    pub fn text(&self, lecture: &Lecture) -> String {
+        match self {
+            Field::Title => lecture.title.clone(),
+            Field::Description => lecture.description.clone().unwrap_or_default(),
+            Field::Modules => {
+                let mut modules = lecture
+                    .categories
+                    .iter()
+                    .flatten()
+                    .flat_map(|(module, categories)| std::iter::once(module).chain(categories))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                modules.sort_unstable();
+                modules.dedup();
+                modules.join(", ")
+            }
+        }
+    }
+}
+
+/// A range within a field of a lecture which matched the search query
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Highlight {
+    pub field: Field,
+    pub range: Range<usize>,
+}
+
+/// A lecture matching a search query
+#[derive(Clone, Debug)]
+pub struct SearchHit<'a> {
+    pub lecture: &'a Lecture,
+    pub score: f32,
+    pub highlights: Vec<Highlight>,
+}
+
+impl<'a> SearchHit<'a> {
+    /// Returns the text of the given field with all matches enclosed in `before` and `after`,
+    /// or `None` if the field does not contain any match
+    pub fn highlighted(&self, field: Field, before: &str, after: &str) -> Option<String> {
+        let mut ranges = self
+            .highlights
+            .iter()
+            .filter(|highlight| highlight.field == field)
+            .map(|highlight| highlight.range.clone())
+            .collect::<Vec<_>>();
+        if ranges.is_empty() {
+            return None;
+        }
+        ranges.sort_by_key(|range| range.start);
+
+        let text = field.text(self.lecture);
+        let mut result = String::with_capacity(text.len());
+        let mut position = 0;
+        for range in ranges {
+            if range.start < position {
+                continue;
+            }
+            result.push_str(&text[position..range.start]);
+            result.push_str(before);
+            result.push_str(&text[range.clone()]);
+            result.push_str(after);
+            position = range.end;
+        }
+        result.push_str(&text[position..]);
+        Some(result)
+    }
+}
+
+struct Posting {
+    lecture: usize,
+    field: Field,
+    range: Range<usize>,
+}
+
+/// Full-text index over lecture titles, descriptions and modules.
+///
+/// Search terms are matched case-insensitively, German umlauts are folded
+/// (so "Uebung" finds "Übung") and common German and English suffixes are ignored.
+/// Terms which are not found literally are matched by prefix or with a small number of typos.
+pub struct SearchIndex<'a> {
+    lectures: Vec<&'a Lecture>,
+    terms: HashMap<String, Vec<Posting>>,
+}
+
+impl<'a> SearchIndex<'a> {
+    pub fn new(lectures: impl IntoIterator<Item = &'a Lecture>) -> Self {
+        let mut index = SearchIndex {
+            lectures: Vec::new(),
+            terms: HashMap::new(),
+        };
+        let mut indexed = HashSet::new();
+        for lecture in lectures {
+            if indexed.insert(&lecture.url) {
+                index.add(lecture);
+            }
+        }
+        index
+    }
+
+    fn add(&mut self, lecture: &'a Lecture) {
+        let id = self.lectures.len();
+        self.lectures.push(lecture);
+
+        for field in Field::ALL {
+            for token in tokenize(&field.text(lecture)) {
+                self.terms.entry(token.term).or_default().push(Posting {
+                    lecture: id,
+                    field,
+                    range: token.range,
+                });
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lectures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lectures.is_empty()
+    }
+
+    /// Returns all lectures matching the query ordered by relevance
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'a>> {
+        let mut terms = tokenize(query)
+            .into_iter()
+            .filter(|token| !is_stop_word(&query[token.range.clone()]))
+            .map(|token| token.term)
+            .collect::<Vec<_>>();
+        if terms.is_empty() {
+            terms = tokenize(query).into_iter().map(|token| token.term).collect();
+        }
+        let mut seen = HashSet::new();
+        terms.retain(|term| seen.insert(term.clone()));
+
+        let mut scores = HashMap::<usize, (f32, usize)>::new();
+        let mut highlights = HashMap::<usize, Vec<Highlight>>::new();
+
+        for term in &terms {
+            let mut best = HashMap::<usize, f32>::new();
+            for (candidate, postings) in &self.terms {
+                let similarity = similarity(term, candidate);
+                if similarity == 0.0 {
+                    continue;
+                }
+                for posting in postings {
+                    let score = similarity * posting.field.weight();
+                    let entry = best.entry(posting.lecture).or_default();
+                    *entry = entry.max(score);
+                    highlights.entry(posting.lecture).or_default().push(Highlight {
+                        field: posting.field,
+                        range: posting.range.clone(),
+                    });
+                }
+            }
+            for (lecture, score) in best {
+                let entry = scores.entry(lecture).or_default();
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+
+        let mut hits = scores
+            .into_iter()
+            .map(|(lecture, (score, matched_terms))| SearchHit {
+                lecture: self.lectures[lecture],
+                // Prefer lectures which match all search terms
+                score: score * matched_terms as f32 / terms.len() as f32,
+                highlights: highlights.remove(&lecture).unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.lecture.title.cmp(&b.lecture.title))
+        });
+        hits
+    }
+}
+
+/// Rates how well an indexed term matches a search term between 0 (no match) and 1 (exact match)
+fn similarity(term: &str, candidate: &str) -> f32 {
+    if term == candidate {
+        return 1.0;
+    }
+    if term.chars().count() >= 3 && candidate.starts_with(term) {
+        return 0.8;
+    }
+    let allowed_typos = match term.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    };
+    match levenshtein(term, candidate, allowed_typos) {
+        Some(1) => 0.6,
+        Some(2) => 0.4,
+        _ => 0.0,
+    }
+}
+
+/// Computes the edit distance between two terms if it does not exceed `max`
+fn levenshtein(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().all(|&distance| distance > max) {
+            return None;
+        }
+        previous = current;
+    }
+
+    Some(previous[b.len()]).filter(|&distance| distance <= max)
+}
+
+struct Token {
+    term: String,
+    range: Range<usize>,
+}
+
+/// Splits text into normalized terms, keeping the byte range of each term in the original text
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(begin)) => {
+                tokens.push(Token {
+                    term: normalize(&text[begin..index]),
+                    range: begin..index,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn normalize(word: &str) -> String {
+    let mut term = String::with_capacity(word.len());
+    for c in word.chars().flat_map(char::to_lowercase) {
+        match c {
+            'ä' => term.push_str("ae"),
+            'ö' => term.push_str("oe"),
+            'ü' => term.push_str("ue"),
+            'ß' => term.push_str("ss"),
+            c => term.push(c),
+        }
+    }
+    stem(&term).to_owned()
+}
+
+/// Strips common German and English inflection suffixes from a term. Longer suffixes are tried first,
+/// so that e.g. "ung" is stripped instead of only its last letter.
+fn stem(term: &str) -> &str {
+    const SUFFIXES: [&str; 9] = ["ungen", "ing", "ung", "en", "er", "es", "e", "s", "n"];
+    const MIN_STEM_LENGTH: usize = 4;
+
+    SUFFIXES
+        .iter()
+        .filter_map(|suffix| term.strip_suffix(suffix))
+        .find(|stem| stem.chars().count() >= MIN_STEM_LENGTH)
+        .unwrap_or(term)
+}
+
+fn is_stop_word(word: &str) -> bool {
+    const STOP_WORDS: [&str; 29] = [
+        "der", "die", "das", "den", "dem", "des", "ein", "eine", "einer", "und", "oder", "in", "im", "mit", "für",
+        "von", "zu", "zur", "auf", "the", "a", "an", "and", "or", "of", "for", "to", "with", "on",
+    ];
+    STOP_WORDS.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lecture(title: &str) -> Lecture {
+        Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/{}.html", title),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_lectures_despite_typos_and_inflection() {
+        let lectures = [
+            lecture("Introduction to Machine Learning"),
+            lecture("Datenbanksysteme I"),
+            lecture("Übung zu Programmiertechnik"),
+        ];
+        let index = SearchIndex::new(&lectures);
+
+        let first = |query: &str| index.search(query).first().map(|hit| hit.lecture.title.clone());
+
+        assert_eq!(first("machine lerning").as_deref(), Some("Introduction to Machine Learning"));
+        assert_eq!(first("Datenbanken").as_deref(), Some("Datenbanksysteme I"));
+        assert_eq!(first("uebung").as_deref(), Some("Übung zu Programmiertechnik"));
+        assert!(index.search("Kryptographie").is_empty());
+    }
+
+    #[test]
+    fn stems_singular_and_plural_alike() {
+        assert_eq!(normalize("Vorlesung"), normalize("Vorlesungen"));
+        assert_eq!(normalize("Vorlesung"), "vorles");
+        assert_eq!(normalize("Datenbank"), normalize("Datenbanken"));
+    }
+
+    #[test]
+    fn repeated_terms_are_weighted_once() {
+        let lectures = [lecture("Datenbanksysteme I"), lecture("Verteilte Systeme")];
+        let index = SearchIndex::new(&lectures);
+
+        let scores = |query: &str| index.search(query).iter().map(|hit| hit.score).collect::<Vec<_>>();
+        assert_eq!(scores("daten systeme daten"), scores("daten systeme"));
+    }
+
+    #[test]
+    fn highlights_matches() {
+        let lectures = [lecture("Introduction to Machine Learning")];
+        let index = SearchIndex::new(&lectures);
+
+        let hit = &index.search("machine")[0];
+        assert_eq!(
+            hit.highlighted(Field::Title, "[", "]").as_deref(),
+            Some("Introduction to [Machine] Learning")
+        );
+    }
+}
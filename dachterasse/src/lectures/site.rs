@@ -0,0 +1,132 @@
+//! Scraping logic shared by the sync and async scrapers. The scrapers only differ in how they send
+//! requests and read responses; which pages are fetched, how responses are checked and what is cached
+//! is decided here.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+use super::config::Config;
+use super::entities::{Lecture, StaticDegree};
+use super::pages::{CachedPage, PageCache, PAGE_CACHE_FILE};
+use super::parse::{self, LectureDetails};
+use super::politeness::{RateLimiter, RobotsCache, RobotsTxt, ScrapeError};
+
+/// The lecture site as seen by a scraper: the pages fetched from it so far, the rate limit of requests
+/// to it and its robots.txt
+pub struct Site {
+    pub base_url: String,
+    pub concurrency: usize,
+    pub pages: PageCache,
+    pub limiter: RateLimiter,
+    /// The site's robots.txt, unless it is ignored
+    robots: Option<RobotsCache>,
+    product_token: String,
+}
+
+impl Site {
+    /// Uses the base URL, concurrency and politeness settings of the config. If a cache path is configured,
+    /// validators of scraped pages are kept there for conditional requests.
+    pub fn from_config(config: &Config) -> Self {
+        Site {
+            base_url: config.base_url.clone(),
+            concurrency: config.concurrency.max(1),
+            pages: match &config.cache_path {
+                Some(path) => PageCache::persistent(Path::new(path).join(PAGE_CACHE_FILE)),
+                None => PageCache::new(),
+            },
+            limiter: RateLimiter::new(config.rate_limit),
+            robots: config.respect_robots_txt.then(RobotsCache::new),
+            product_token: config.product_token().to_owned(),
+        }
+    }
+
+    /// The URL of the degree's lecture overview page on this site
+    pub fn overview_url(&self, degree: &StaticDegree) -> String {
+        parse::resolve_url(degree.url, &self.base_url)
+    }
+
+    /// The site's robots.txt if it is ignored or was fetched recently, otherwise the URL to fetch it from
+    pub fn robots(&self) -> Result<Arc<RobotsTxt>, String> {
+        match &self.robots {
+            None => Ok(Arc::new(RobotsTxt::allow_all())),
+            Some(cache) => cache.get().ok_or_else(|| format!("{}/robots.txt", self.base_url)),
+        }
+    }
+
+    /// Whether the robots.txt response is read. Missing robots.txt files allow everything, while other
+    /// unsuccessful responses are errors.
+    pub fn has_robots(&self, url: &str, status: StatusCode) -> Result<bool, ScrapeError> {
+        if status.is_client_error() {
+            return Ok(false);
+        }
+        check_status(url, status)?;
+        Ok(true)
+    }
+
+    /// Caches the fetched robots.txt, or one allowing everything if the site has none
+    pub fn set_robots(&self, document: Option<&str>) -> Arc<RobotsTxt> {
+        let robots = match document {
+            Some(document) => RobotsTxt::parse(document, &self.product_token),
+            None => RobotsTxt::allow_all(),
+        };
+        if let Some(delay) = robots.crawl_delay() {
+            self.limiter.slow_down(delay);
+        }
+        match &self.robots {
+            Some(cache) => cache.set(robots),
+            None => Arc::new(robots),
+        }
+    }
+
+    /// Parses an overview page and caches it with the validators of its response
+    pub fn set_overview(&self, url: &str, headers: &HeaderMap, document: &str) -> Vec<Lecture> {
+        let page = CachedPage::new(headers, parse::parse_lectures(document, &self.base_url));
+        self.pages.set_overview(url, &page);
+        page.content
+    }
+
+    /// Parses a lecture detail page and caches it with the validators of its response
+    pub fn set_details(
+        &self,
+        url: &str,
+        degree: &StaticDegree,
+        headers: &HeaderMap,
+        document: &str,
+    ) -> LectureDetails {
+        let page = CachedPage::new(headers, parse::parse_lecture_details(document, degree.name));
+        self.pages.set_details(url, degree.name, &page);
+        page.content
+    }
+
+    /// Writes the validators of scraped pages to the cache directory, if any
+    pub fn save_pages(&self) {
+        if let Err(err) = self.pages.save() {
+            eprintln!("Could not save validators of scraped pages due to {}", err);
+        }
+    }
+}
+
+/// The content of the cached page if the site answered a conditional request with `304 Not Modified`,
+/// or `None` if the response has to be parsed. Unsuccessful responses are errors, so that error pages
+/// are neither parsed nor cached.
+pub fn unchanged<T>(
+    url: &str,
+    status: StatusCode,
+    cached: Option<CachedPage<T>>,
+) -> Result<Option<T>, ScrapeError> {
+    match (status, cached) {
+        (StatusCode::NOT_MODIFIED, Some(cached)) => Ok(Some(cached.content)),
+        (status, _) => check_status(url, status).map(|_| None),
+    }
+}
+
+fn check_status(url: &str, status: StatusCode) -> Result<(), ScrapeError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(ScrapeError::Status(url.to_owned(), status))
+    }
+}
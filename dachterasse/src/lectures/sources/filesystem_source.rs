@@ -2,15 +2,23 @@ use std::fs::File;
 use std::{fs, io};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use crate::{StaticDegree, Degrees, Lecture};
 use crate::datasource::{LoadResult, SaveResult, ReadWriteDataSource, ReadOnlyDataSource};
 
 pub struct FSDataSource {
-    caches: HashMap<&'static StaticDegree, FileSystemCache>
+    caches: HashMap<&'static StaticDegree, FileSystemCache>,
+    ttl: Option<Duration>,
 }
 
 impl ReadOnlyDataSource for FSDataSource {
     fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult {
+        if let Some(ttl) = self.ttl {
+            if self.is_stale(degree, ttl) {
+                return Err(format!("Cache for degree {} is missing or outdated", degree.id));
+            }
+        }
+
         self.cache_for_degree(degree)
             .load_lectures()
             .map_err(|err| format!("Could not load cache for degree {} due to {}", degree.id, err))
@@ -23,6 +31,11 @@ impl ReadWriteDataSource for FSDataSource {
             .save_lectures(lectures)
             .map_err(|err| format!("Could not save cache for degree {} due to {}", degree.id, err))
     }
+
+    fn clear_lectures(&mut self, degree: &'static StaticDegree) -> SaveResult {
+        self.clear(degree)
+            .map_err(|err| format!("Could not clear cache for degree {} due to {}", degree.id, err))
+    }
 }
 
 impl FSDataSource {
@@ -33,7 +46,31 @@ impl FSDataSource {
             caches.insert(degree, FileSystemCache { path: Path::join(path.as_ref(), degree.id)});
         }
 
-        FSDataSource { caches }
+        FSDataSource { caches, ttl: None }
+    }
+
+    /// Treats cached lectures which are older than `ttl` as missing, so that they are loaded from other sources again
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns information about the cached lectures of a degree or `None` if nothing was cached yet
+    pub fn status(&self, degree: &'static StaticDegree) -> io::Result<Option<CacheStatus>> {
+        self.cache_for_degree(degree).status()
+    }
+
+    /// Whether the lectures of a degree were not cached yet or were cached longer than `max_age` ago
+    pub fn is_stale(&self, degree: &'static StaticDegree, max_age: Duration) -> bool {
+        match self.status(degree) {
+            Ok(Some(status)) => status.age() > max_age,
+            _ => true,
+        }
+    }
+
+    /// Deletes the cached lectures of a degree
+    pub fn clear(&self, degree: &'static StaticDegree) -> io::Result<()> {
+        self.cache_for_degree(degree).clear()
     }
 
     fn cache_for_degree(&self, degree: &'static StaticDegree) -> &FileSystemCache {
@@ -41,6 +78,24 @@ impl FSDataSource {
     }
 }
 
+/// Information about the cached lectures of a degree
+#[derive(Debug, Clone)]
+pub struct CacheStatus {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    /// Size of the cache file in bytes
+    pub size: u64,
+    /// Number of cached lectures
+    pub lectures: usize,
+}
+
+impl CacheStatus {
+    /// Time since the cache was last written
+    pub fn age(&self) -> Duration {
+        self.modified.elapsed().unwrap_or_default()
+    }
+}
+
 struct FileSystemCache {
     path: PathBuf
 }
@@ -53,6 +108,29 @@ impl FileSystemCache {
     pub fn save_lectures(&self, lectures: &[Lecture]) -> io::Result<()> {
         save_cache_to(&self.path, lectures)
     }
+
+    pub fn status(&self) -> io::Result<Option<CacheStatus>> {
+        let path = ensure_extension(&self.path, "json");
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Some(CacheStatus {
+            path: path.to_path_buf(),
+            modified: metadata.modified()?,
+            size: metadata.len(),
+            lectures: self.load_lectures()?.len(),
+        }))
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(ensure_extension(&self.path, "json")) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Attempts to load cached lecture information from a JSON file
@@ -69,14 +147,6 @@ fn save_cache_to<P: AsRef<Path>>(path: &P, cache: &[Lecture]) -> io::Result<()>
     Ok(())
 }
 
-/// Serializes cache to JSON formatted with "pretty"-option and writes it to a file
-#[allow(dead_code)]
-fn save_cache_pretty<P: AsRef<Path>>(path: &P, cache: &[Lecture]) -> io::Result<()> {
-    let file = create_cache(path)?;
-    serde_json::to_writer_pretty(file, cache)?;
-    Ok(())
-}
-
 fn create_cache<P: AsRef<Path>>(path: &P) -> io::Result<File> {
     create_parent_directory(path)?;
     File::create(ensure_extension(path, "json"))
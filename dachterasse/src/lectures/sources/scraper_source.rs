@@ -1,3 +1,4 @@
+use crate::Config;
 use crate::datasource::{LoadResult, ReadOnlyDataSource};
 use crate::StaticDegree;
 use crate::scrape::LectureScraper;
@@ -11,6 +12,10 @@ impl ScraperSource {
     pub fn new() -> Self {
         ScraperSource { scraper: LectureScraper::new() }
     }
+
+    pub fn from_config(config: &Config) -> Self {
+        ScraperSource { scraper: LectureScraper::from_config(config) }
+    }
 }
 
 impl ReadOnlyDataSource for ScraperSource {
@@ -11,7 +11,35 @@ mod tests {
 extern crate serde;
 
 mod lectures {
+    pub mod catalogue;
     pub mod entities;
+    pub mod export {
+        mod csv;
+        mod format;
+        mod html;
+        mod ics;
+        mod json;
+        mod markdown;
+
+        pub use csv::{write_csv_row, CsvExporter};
+        pub use format::{Exporter, Format};
+        pub use html::HtmlExporter;
+        pub use ics::IcsExporter;
+        pub use json::JsonExporter;
+        pub use markdown::MarkdownExporter;
+    }
+    pub mod loaded;
+    // Only the scrapers parse pages, the other features merely share its types
+    #[cfg_attr(not(any(feature = "sync", feature = "async")), allow(dead_code))]
+    pub mod parse;
+    pub mod plan;
+    #[cfg(feature = "client")]
+    pub mod politeness;
+    pub mod query;
+    pub mod schedule;
+    pub mod search;
+    #[cfg(any(feature = "sync", feature = "async"))]
+    pub(crate) mod site;
 
     #[cfg(feature = "sync")]
     pub mod scrape;
@@ -27,15 +55,20 @@ mod lectures {
         mod memory_source;
         mod scraper_source;
 
-        pub use filesystem_source::FSDataSource;
+        pub use filesystem_source::{CacheStatus, FSDataSource};
         pub use memory_source::InMemoryDataSource;
         pub use scraper_source::ScraperSource;
     }
 
-    #[cfg(feature = "client")]
+    #[cfg(all(feature = "sync", feature = "async"))]
+    pub mod adapters;
+
+    #[cfg(feature = "sync")]
     pub mod client;
     #[cfg(feature = "client")]
     pub mod config;
+    #[cfg(feature = "client")]
+    pub mod pages;
 }
 
 #[cfg(feature = "async")]
@@ -47,14 +80,19 @@ pub mod asynch {
         pub mod repository;
 
         pub mod sources {
+            mod filesystem_source;
             mod memory_source;
             mod scraper_source;
 
+            pub use filesystem_source::FSDataSource;
             pub use memory_source::InMemoryDataSource;
             pub use scraper_source::ScraperSource;
         }
+
+        pub mod client;
     }
     pub use lectures::*;
+    pub use lectures::client::LectureClient;
 }
 
 #[cfg(feature = "sync")]
@@ -63,14 +101,28 @@ pub use crate::lectures::client::LectureClient;
 pub use crate::lectures::config::Config;
 pub use crate::lectures::entities::Degree;
 pub use crate::lectures::entities::Degrees;
+pub use crate::lectures::entities::Language;
 pub use crate::lectures::entities::Lecture;
 pub use crate::lectures::entities::StaticDegree;
 
-#[cfg(feature = "client")]
+#[cfg(all(feature = "sync", feature = "async"))]
+pub use crate::lectures::adapters;
+pub use crate::lectures::catalogue;
+pub use crate::lectures::export;
+pub use crate::lectures::loaded;
+pub use crate::lectures::plan;
+pub use crate::lectures::query;
+pub use crate::lectures::schedule;
+pub use crate::lectures::search;
+#[cfg(feature = "sync")]
 pub use crate::lectures::datasource;
 #[cfg(feature = "client")]
-pub use crate::lectures::repository;
+pub use crate::lectures::pages;
 #[cfg(feature = "client")]
+pub use crate::lectures::politeness;
+#[cfg(feature = "sync")]
+pub use crate::lectures::repository;
+#[cfg(feature = "sync")]
 pub use crate::lectures::scrape;
-#[cfg(feature = "client")]
+#[cfg(feature = "sync")]
 pub use crate::lectures::sources;
@@ -1,3 +1,4 @@
+-- Databases created before migrations were introduced already contain this table
 CREATE TABLE IF NOT EXISTS lectures (
     title VARCHAR(255) NOT NULL,
     url TEXT NOT NULL, 
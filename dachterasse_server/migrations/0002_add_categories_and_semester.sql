@@ -0,0 +1,3 @@
+-- Modules and their categories in which a lecture can be taken, as JSON object
+ALTER TABLE lectures ADD COLUMN categories TEXT;
+ALTER TABLE lectures ADD COLUMN semester VARCHAR(64);
@@ -0,0 +1,3 @@
+-- Unix timestamps in seconds of when a lecture was first and last saved
+ALTER TABLE lectures ADD COLUMN created_at BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE lectures ADD COLUMN updated_at BIGINT NOT NULL DEFAULT 0;
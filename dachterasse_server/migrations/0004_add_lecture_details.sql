@@ -0,0 +1,6 @@
+-- Details from the lecture page. Lecturers, language and schedule are stored as JSON.
+ALTER TABLE lectures ADD COLUMN description TEXT;
+ALTER TABLE lectures ADD COLUMN lecturers TEXT;
+ALTER TABLE lectures ADD COLUMN ects SMALLINT;
+ALTER TABLE lectures ADD COLUMN language VARCHAR(16);
+ALTER TABLE lectures ADD COLUMN schedule TEXT;
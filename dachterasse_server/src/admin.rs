@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use dachterasse::asynch::repository::SourceHealth;
+use dachterasse::Config;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Request guard for admin endpoints, which require the configured admin token as bearer token.
+/// If no admin token is configured, admin endpoints are not found.
+pub struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let expected = request
+            .rocket()
+            .state::<Config>()
+            .and_then(|config| config.admin_token.as_deref());
+        let Some(expected) = expected else {
+            return Outcome::Error((Status::NotFound, ()));
+        };
+
+        let token = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match token {
+            Some(token) if constant_time_eq(token.trim().as_bytes(), expected.as_bytes()) => Outcome::Success(Admin),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Compares without returning early, so that response times do not reveal how much of a token was correct
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Result of a forced refresh of a degree's lectures
+#[derive(Serialize, JsonSchema)]
+pub struct Refreshed {
+    pub degree: &'static str,
+    pub lectures: usize,
+}
+
+/// Health of a data source together with the age of its lectures per degree
+#[derive(Serialize, JsonSchema)]
+pub struct SourceReport {
+    #[serde(flatten)]
+    pub health: SourceHealth,
+    /// Seconds since the lectures of each degree were last updated in the source, by degree id
+    pub age: HashMap<&'static str, i64>,
+}
+
+impl From<SourceHealth> for SourceReport {
+    fn from(health: SourceHealth) -> Self {
+        let now = Utc::now();
+        let age = health
+            .updated
+            .iter()
+            .map(|(&degree, updated)| (degree, (now - *updated).num_seconds()))
+            .collect();
+        SourceReport { health, age }
+    }
+}
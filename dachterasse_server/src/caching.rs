@@ -0,0 +1,160 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{PoisonError, RwLock, RwLockWriteGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dachterasse::{Lecture, StaticDegree};
+use rocket::http::{Header, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json;
+
+/// Seconds for which clients and CDNs may reuse a response without revalidating it
+pub const MAX_AGE_SECONDS: u32 = 300;
+
+/// Version of the lecture data a response is built from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Version {
+    /// Strong entity tag including the quotes, e.g. `"5c1e0c6f2a9b3d47"`
+    pub etag: String,
+    /// Time at which the data was scraped or written to the source it was loaded from, truncated to seconds
+    pub modified: SystemTime,
+}
+
+impl Version {
+    /// The version of a response which is built from the data of all given versions
+    pub fn combine(versions: &[Version]) -> Version {
+        let mut hasher = DefaultHasher::new();
+        for version in versions {
+            version.etag.hash(&mut hasher);
+        }
+        Version {
+            etag: format!("\"{:016x}\"", hasher.finish()),
+            modified: versions
+                .iter()
+                .map(|version| version.modified)
+                .max()
+                .unwrap_or(UNIX_EPOCH),
+        }
+    }
+
+    /// Whether the client already has this version according to `If-None-Match` or, if that header
+    /// is missing, `If-Modified-Since`
+    pub fn is_current(&self, request: &Request) -> bool {
+        let headers = request.headers();
+        if headers.contains("If-None-Match") {
+            return headers
+                .get("If-None-Match")
+                .flat_map(|tags| tags.split(','))
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == self.etag);
+        }
+
+        headers
+            .get_one("If-Modified-Since")
+            .and_then(|date| httpdate::parse_http_date(date).ok())
+            .is_some_and(|since| self.modified <= since)
+    }
+}
+
+/// The lectures served for a degree together with their version
+#[derive(Clone)]
+pub struct Snapshot {
+    pub lectures: Vec<Lecture>,
+    pub version: Version,
+}
+
+/// The lectures served for each degree. Their version is computed once when they are loaded or refreshed,
+/// so that requests only compare it with the client's.
+#[derive(Default)]
+pub struct Snapshots {
+    snapshots: RwLock<HashMap<&'static str, Snapshot>>,
+}
+
+impl Snapshots {
+    pub fn new() -> Self {
+        Snapshots::default()
+    }
+
+    /// The lectures of a degree, unless they were not loaded yet or were removed
+    pub fn get(&self, degree: &'static StaticDegree) -> Option<Snapshot> {
+        let snapshots = self.snapshots.read().unwrap_or_else(PoisonError::into_inner);
+        snapshots.get(degree.id).cloned()
+    }
+
+    /// Serves the given lectures of a degree from now on. `modified` is the time at which they were scraped
+    /// or written to the source they were loaded from. If the lectures did not change, their version is kept.
+    pub fn set(&self, degree: &'static StaticDegree, lectures: Vec<Lecture>, modified: SystemTime) -> Snapshot {
+        let etag = fingerprint(&lectures);
+        let mut snapshots = self.write();
+        let version = match snapshots.get(degree.id) {
+            Some(snapshot) if snapshot.version.etag == etag => snapshot.version.clone(),
+            _ => Version {
+                etag,
+                modified: truncate(modified),
+            },
+        };
+
+        let snapshot = Snapshot { lectures, version };
+        snapshots.insert(degree.id, snapshot.clone());
+        snapshot
+    }
+
+    /// Stops serving the lectures of a degree, so that they are loaded again on the next request
+    pub fn remove(&self, degree: &'static StaticDegree) {
+        self.write().remove(degree.id);
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<&'static str, Snapshot>> {
+        self.snapshots.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Hashes the lectures independently of the order of their categories, which are stored in hash maps
+fn fingerprint(lectures: &[Lecture]) -> String {
+    let canonical = json::to_value(lectures).map(|value| value.to_string()).unwrap_or_default();
+    let mut hasher = DefaultHasher::new();
+    canonical.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// Truncates the time to seconds, the precision of HTTP dates
+fn truncate(time: SystemTime) -> SystemTime {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+/// A response with `ETag`, `Last-Modified` and `Cache-Control` headers, which is answered with
+/// `304 Not Modified` instead if the client's copy is still current
+pub struct Cached<R> {
+    pub inner: R,
+    pub version: Version,
+}
+
+impl<R> Cached<R> {
+    pub fn new(inner: R, version: Version) -> Self {
+        Cached { inner, version }
+    }
+}
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for Cached<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = if self.version.is_current(request) {
+            Response::build().status(Status::NotModified).finalize()
+        } else {
+            self.inner.respond_to(request)?
+        };
+
+        response.set_header(Header::new("ETag", self.version.etag));
+        response.set_header(Header::new(
+            "Last-Modified",
+            httpdate::fmt_http_date(self.version.modified),
+        ));
+        response.set_header(Header::new(
+            "Cache-Control",
+            format!("public, max-age={}", MAX_AGE_SECONDS),
+        ));
+        Ok(response)
+    }
+}
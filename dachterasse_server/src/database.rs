@@ -2,52 +2,268 @@ use dachterasse::asynch::datasource::{
     LoadResult, ReadOnlyDataSource, ReadWriteDataSource, SaveResult,
 };
 use dachterasse::StaticDegree;
+use rocket::serde::json;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
-use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use sqlx::migrate::Migrator;
+use sqlx::FromRow;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Versioned schema migrations in `migrations/`. Applied migrations are recorded in the `_sqlx_migrations` table.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
 
 #[derive(Serialize, FromRow)]
 struct Lecture {
     title: String,
     url: String,
     degree: String,
+    description: Option<String>,
+    /// JSON object of modules and their categories
+    categories: Option<String>,
+    /// JSON array of names
+    lecturers: Option<String>,
+    ects: Option<i16>,
+    /// JSON string, e.g. `"German"`
+    language: Option<String>,
+    semester: Option<String>,
+    /// JSON object of the lecture's sessions
+    schedule: Option<String>,
+}
+
+impl Lecture {
+    fn new(lecture: &dachterasse::Lecture, degree: &'static StaticDegree) -> Self {
+        Lecture {
+            title: lecture.title.clone(),
+            url: lecture.url.clone(),
+            degree: degree.id.to_owned(),
+            description: lecture.description.clone(),
+            categories: lecture.categories.as_ref().and_then(to_json),
+            lecturers: to_json(&lecture.lecturers),
+            ects: lecture.ects.map(i16::from),
+            language: lecture.language.as_ref().and_then(to_json),
+            semester: lecture.semester.clone(),
+            schedule: lecture.schedule.as_ref().and_then(to_json),
+        }
+    }
+}
+
+impl From<Lecture> for dachterasse::Lecture {
+    fn from(lecture: Lecture) -> Self {
+        dachterasse::Lecture {
+            title: lecture.title,
+            url: lecture.url,
+            description: lecture.description,
+            categories: from_json(lecture.categories),
+            lecturers: from_json(lecture.lecturers).unwrap_or_default(),
+            ects: lecture.ects.and_then(|ects| u8::try_from(ects).ok()),
+            language: from_json(lecture.language),
+            semester: lecture.semester,
+            schedule: from_json(lecture.schedule),
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Option<String> {
+    json::to_string(value).ok()
+}
+
+fn from_json<T: DeserializeOwned>(value: Option<String>) -> Option<T> {
+    value.and_then(|value| json::from_str(&value).ok())
+}
+
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
 }
 
-pub struct LectureDatabase {
-    pool: PgPool,
+/// Stores lectures in a Postgres or SQLite database, depending on the enabled features
+pub enum LectureDatabase {
+    #[cfg(feature = "postgres")]
+    Postgres(sqlx::PgPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::SqlitePool),
 }
 
 impl LectureDatabase {
-    pub fn new(pool: PgPool) -> Self {
-        LectureDatabase { pool }
+    /// Connects to the database at the URL, e.g. `postgres://localhost/dachterasse` or `sqlite://lectures.db`.
+    /// SQLite databases are created if they do not exist yet.
+    pub async fn connect(url: &str) -> Result<Self, String> {
+        #[cfg(feature = "postgres")]
+        if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+            let pool = sqlx::PgPool::connect(url).await.map_err(|e| e.to_string())?;
+            return Ok(LectureDatabase::Postgres(pool));
+        }
+
+        #[cfg(feature = "sqlite")]
+        if url.starts_with("sqlite:") {
+            use std::str::FromStr;
+
+            let options = sqlx::sqlite::SqliteConnectOptions::from_str(url)
+                .map_err(|e| e.to_string())?
+                .create_if_missing(true);
+            // Every connection to an in-memory database opens a new, empty database
+            let connections = if url.contains(":memory:") { 1 } else { 10 };
+            let pool = sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(connections)
+                .connect_with(options)
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(LectureDatabase::Sqlite(pool));
+        }
+
+        Err(format!(
+            "Unsupported database URL {}. This server supports {}.",
+            url,
+            SUPPORTED_DATABASES.join(" and ")
+        ))
+    }
+
+    /// Applies all migrations which were not applied to the database yet
+    pub async fn migrate(&self) -> Result<(), String> {
+        match self {
+            #[cfg(feature = "postgres")]
+            LectureDatabase::Postgres(pool) => MIGRATOR.run(pool).await,
+            #[cfg(feature = "sqlite")]
+            LectureDatabase::Sqlite(pool) => MIGRATOR.run(pool).await,
+        }
+        .map_err(|e| format!("Could not migrate database: {}", e))
     }
 }
 
-#[async_trait]
-impl ReadOnlyDataSource for LectureDatabase {
-    async fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult {
-        let database_lectures: Vec<Lecture> =
-            sqlx::query_as("SELECT * FROM lectures WHERE id = $1")
+const SUPPORTED_DATABASES: &[&str] = &[
+    #[cfg(feature = "postgres")]
+    "postgres://",
+    #[cfg(feature = "sqlite")]
+    "sqlite://",
+];
+
+/// Queries shared by all database backends. Parameters are written as `$1`, which both Postgres and SQLite accept.
+macro_rules! queries {
+    ($backend:ident, $database:ty) => {
+        mod $backend {
+            use super::*;
+            use sqlx::{Pool, QueryBuilder};
+
+            pub async fn load_lectures(pool: &Pool<$database>, degree: &'static StaticDegree) -> LoadResult {
+                let database_lectures: Vec<Lecture> = sqlx::query_as(
+                    "SELECT title, url, degree, description, categories, lecturers, ects, language, semester, schedule \
+                     FROM lectures WHERE degree = $1",
+                )
                 .bind(degree.id)
-                .fetch_all(&self.pool)
+                .fetch_all(pool)
                 .await
                 .map_err(|e| e.to_string())?;
 
-        let lectures = database_lectures
-            .iter()
-            .map(|lecture| dachterasse::Lecture {
-                title: lecture.title.to_owned(),
-                url: lecture.url.to_owned(),
-                categories: None,
-            })
-            .collect::<Vec<_>>();
-
-        if lectures.is_empty() {
-            Err(format!(
-                "No lectures in database for degree {}",
-                degree.name
-            ))
-        } else {
-            Ok(lectures)
+                let lectures = database_lectures
+                    .into_iter()
+                    .map(dachterasse::Lecture::from)
+                    .collect::<Vec<_>>();
+
+                if lectures.is_empty() {
+                    Err(format!(
+                        "No lectures in database for degree {}",
+                        degree.name
+                    ))
+                } else {
+                    Ok(lectures)
+                }
+            }
+
+            /// Replaces the stored lectures of the degree within one transaction. Lectures which were stored
+            /// before keep their creation time.
+            pub async fn save_lectures(
+                pool: &Pool<$database>,
+                degree: &'static StaticDegree,
+                lectures: &[dachterasse::Lecture],
+            ) -> SaveResult {
+                let now = unix_time();
+                let mut urls = HashSet::new();
+                let lectures = lectures
+                    .iter()
+                    .filter(|lecture| urls.insert(lecture.url.as_str()))
+                    .collect::<Vec<_>>();
+                let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+
+                let mut delete: QueryBuilder<$database> = QueryBuilder::new("DELETE FROM lectures WHERE degree = ");
+                delete.push_bind(degree.id);
+                if !lectures.is_empty() {
+                    delete.push(" AND url NOT IN (");
+                    let mut separated = delete.separated(", ");
+                    for lecture in &lectures {
+                        separated.push_bind(lecture.url.clone());
+                    }
+                    separated.push_unseparated(")");
+                }
+                delete
+                    .build()
+                    .execute(&mut transaction)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                if !lectures.is_empty() {
+                    let mut insert: QueryBuilder<$database> = QueryBuilder::new(
+                        "INSERT INTO lectures(title, url, degree, description, categories, lecturers, ects, language, \
+                         semester, schedule, created_at, updated_at)",
+                    );
+                    insert.push_values(&lectures, |mut b, lecture| {
+                        let lecture = Lecture::new(lecture, degree);
+                        b.push_bind(lecture.title)
+                            .push_bind(lecture.url)
+                            .push_bind(lecture.degree)
+                            .push_bind(lecture.description)
+                            .push_bind(lecture.categories)
+                            .push_bind(lecture.lecturers)
+                            .push_bind(lecture.ects)
+                            .push_bind(lecture.language)
+                            .push_bind(lecture.semester)
+                            .push_bind(lecture.schedule)
+                            .push_bind(now)
+                            .push_bind(now);
+                    });
+                    insert.push(
+                        " ON CONFLICT (url, degree) DO UPDATE SET title = excluded.title, \
+                         description = excluded.description, categories = excluded.categories, \
+                         lecturers = excluded.lecturers, ects = excluded.ects, language = excluded.language, \
+                         semester = excluded.semester, schedule = excluded.schedule, updated_at = excluded.updated_at",
+                    );
+                    insert
+                        .build()
+                        .execute(&mut transaction)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+
+                transaction.commit().await.map_err(|e| e.to_string())
+            }
+
+            pub async fn clear_lectures(pool: &Pool<$database>, degree: &'static StaticDegree) -> SaveResult {
+                sqlx::query("DELETE FROM lectures WHERE degree = $1")
+                    .bind(degree.id)
+                    .execute(pool)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "postgres")]
+queries!(postgres, sqlx::Postgres);
+#[cfg(feature = "sqlite")]
+queries!(sqlite, sqlx::Sqlite);
+
+#[async_trait]
+impl ReadOnlyDataSource for LectureDatabase {
+    async fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult {
+        match self {
+            #[cfg(feature = "postgres")]
+            LectureDatabase::Postgres(pool) => postgres::load_lectures(pool, degree).await,
+            #[cfg(feature = "sqlite")]
+            LectureDatabase::Sqlite(pool) => sqlite::load_lectures(pool, degree).await,
         }
     }
 }
@@ -59,26 +275,85 @@ impl ReadWriteDataSource for LectureDatabase {
         degree: &'static StaticDegree,
         lectures: &[dachterasse::Lecture],
     ) -> SaveResult {
-        let database_lectures = lectures.iter().map(|lecture| Lecture {
-            title: lecture.title.clone(),
-            url: lecture.url.clone(),
-            degree: degree.id.to_owned(),
-        });
+        match self {
+            #[cfg(feature = "postgres")]
+            LectureDatabase::Postgres(pool) => postgres::save_lectures(pool, degree, lectures).await,
+            #[cfg(feature = "sqlite")]
+            LectureDatabase::Sqlite(pool) => sqlite::save_lectures(pool, degree, lectures).await,
+        }
+    }
 
-        let mut query_builder: QueryBuilder<Postgres> =
-            QueryBuilder::new("INSERT INTO lectures(title, url, degree_id)");
+    async fn clear_lectures(&self, degree: &'static StaticDegree) -> SaveResult {
+        match self {
+            #[cfg(feature = "postgres")]
+            LectureDatabase::Postgres(pool) => postgres::clear_lectures(pool, degree).await,
+            #[cfg(feature = "sqlite")]
+            LectureDatabase::Sqlite(pool) => sqlite::clear_lectures(pool, degree).await,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use dachterasse::Degrees;
 
-        query_builder.push_values(database_lectures, |mut b, lecture| {
-            b.push_bind(lecture.title)
-                .push_bind(lecture.url)
-                .push_bind(degree.id);
-        });
+    #[rocket::async_test]
+    async fn stores_lectures_in_sqlite() {
+        let database = LectureDatabase::connect("sqlite::memory:").await.unwrap();
+        database.migrate().await.unwrap();
+        database.migrate().await.unwrap();
+        let lecture = |title: &str| dachterasse::Lecture {
+            title: title.to_owned(),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            categories: Some([(String::from("Softwarebasissysteme"), vec![])].into()),
+            semester: Some(String::from("WiSe 2022/23")),
+            ..Default::default()
+        };
+        let other = dachterasse::Lecture {
+            url: String::from("https://hpi.de/studium/pt1.html"),
+            ..Default::default()
+        };
 
-        query_builder
-            .build()
-            .execute(&self.pool)
+        database
+            .save_lectures(&Degrees::ITSE_BA, &[lecture("DBS I"), other])
             .await
-            .map(|_| ())
-            .map_err(|e| e.to_string())
+            .unwrap();
+        database
+            .save_lectures(&Degrees::ITSE_BA, &[lecture("Datenbanksysteme I")])
+            .await
+            .unwrap();
+        let loaded = database.load_lectures(&Degrees::ITSE_BA).await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].title, "Datenbanksysteme I");
+        assert_eq!(loaded[0].categories, lecture("").categories);
+        assert_eq!(loaded[0].semester.as_deref(), Some("WiSe 2022/23"));
+
+        database.clear_lectures(&Degrees::ITSE_BA).await.unwrap();
+        assert!(database.load_lectures(&Degrees::ITSE_BA).await.is_err());
+    }
+
+    #[rocket::async_test]
+    async fn stores_all_fields_of_lectures() {
+        let database = LectureDatabase::connect("sqlite::memory:").await.unwrap();
+        database.migrate().await.unwrap();
+        let lecture = dachterasse::Lecture {
+            title: String::from("Datenbanksysteme I"),
+            url: String::from("https://hpi.de/studium/dbs1.html"),
+            description: Some(String::from("Relationale Datenbanken und SQL")),
+            categories: Some([(String::from("Softwarebasissysteme"), vec![String::from("Datenbanken")])].into()),
+            lecturers: vec![String::from("Prof. Dr. Felix Naumann")],
+            ects: Some(6),
+            language: Some(dachterasse::Language::German),
+            semester: Some(String::from("WiSe 2022/23")),
+            schedule: Some(dachterasse::schedule::parse_schedule([
+                "Montag, 09:15 - 10:45 Uhr, HS 1",
+                "14.02.2023, 13:30-15:00, HS 2",
+            ])),
+        };
+
+        database.save_lectures(&Degrees::ITSE_BA, std::slice::from_ref(&lecture)).await.unwrap();
+        let loaded = database.load_lectures(&Degrees::ITSE_BA).await.unwrap();
+        assert_eq!(json::to_value(&loaded).unwrap(), json::to_value([lecture]).unwrap());
     }
 }
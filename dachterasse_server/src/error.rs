@@ -0,0 +1,113 @@
+use std::io::Cursor;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Seconds after which clients should retry a request which failed because no source returned lectures
+pub const RETRY_AFTER_SECONDS: u32 = 60;
+
+/// An error response of the API, sent as `application/problem+json` (RFC 7807)
+#[derive(Debug)]
+pub enum ApiError {
+    /// No enabled degree with the given id exists
+    UnknownDegree(String),
+    /// No lecture with the given id is offered in any enabled degree
+    UnknownLecture(String),
+    /// Every data source failed to return lectures
+    Unavailable(String),
+    /// The request lacks a valid bearer token
+    Unauthorized,
+    /// Any other error with the given status
+    Other(Status, String),
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Problem details as defined in RFC 7807
+#[derive(Serialize, JsonSchema, Debug)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: &'static str,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+}
+
+impl Problem {
+    pub fn new(status: Status, detail: String) -> Self {
+        Problem {
+            problem_type: "about:blank",
+            title: status.reason_lossy().to_owned(),
+            status: status.code,
+            detail,
+        }
+    }
+}
+
+impl ApiError {
+    pub fn status(&self) -> Status {
+        match self {
+            ApiError::UnknownDegree(_) | ApiError::UnknownLecture(_) => Status::NotFound,
+            ApiError::Unavailable(_) => Status::ServiceUnavailable,
+            ApiError::Unauthorized => Status::Unauthorized,
+            ApiError::Other(status, _) => *status,
+        }
+    }
+
+    fn detail(self) -> String {
+        match self {
+            ApiError::UnknownDegree(id) => format!("No degree program with the id '{}' exists", id),
+            ApiError::UnknownLecture(id) => format!("No lecture with the id '{}' exists", id),
+            ApiError::Unavailable(reason) => format!("Lectures are currently unavailable: {}", reason),
+            ApiError::Unauthorized => String::from("A valid bearer token is required"),
+            ApiError::Other(_, detail) => detail,
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let retry = matches!(self, ApiError::Unavailable(_));
+        let authenticate = matches!(self, ApiError::Unauthorized);
+        let status = self.status();
+        let mut response = Problem::new(status, self.detail()).respond_to(request)?;
+        if retry {
+            response.set_header(Header::new("Retry-After", RETRY_AFTER_SECONDS.to_string()));
+        }
+        if authenticate {
+            response.set_header(Header::new("WWW-Authenticate", "Bearer"));
+        }
+        Ok(response)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for Problem {
+    fn respond_to(self, _request: &'r Request<'_>) -> response::Result<'static> {
+        let body = json::to_string(&self).map_err(|_| Status::InternalServerError)?;
+        Response::build()
+            .status(Status::new(self.status))
+            .header(ContentType::new("application", "problem+json"))
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
+
+/// Answers requests which did not match any route or failed without an `ApiError` with problem details
+#[catch(default)]
+pub fn default_catcher(status: Status, request: &Request) -> Problem {
+    let detail = match status.code {
+        404 => format!("No resource found at {}", request.uri()),
+        _ => String::from(status.reason_lossy()),
+    };
+    Problem::new(status, detail)
+}
+
+/// Answers requests to admin endpoints without a valid bearer token
+#[catch(401)]
+pub fn unauthorized_catcher() -> ApiError {
+    ApiError::Unauthorized
+}
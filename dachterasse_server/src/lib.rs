@@ -3,18 +3,60 @@ extern crate rocket;
 #[macro_use]
 extern crate rocket_modules;
 
-use shuttle_service::{error::CustomError, ShuttleRocket};
-use sqlx::{Executor, PgPool};
+use dachterasse::Config;
+use rocket::{Build, Rocket};
 
 pub mod server;
 
+mod admin;
+mod caching;
 mod cors;
-mod database;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub mod database;
+mod error;
+mod listing;
+mod openapi;
 
+/// Builds the server from the config. Lectures are stored in the database at `database_url` if one is configured.
+pub async fn rocket(config: Config) -> Result<Rocket<Build>, String> {
+    let database = match &config.database_url {
+        Some(url) => Some(connect(url).await?),
+        None => None,
+    };
+    Ok(server::rocket(database, config))
+}
+
+/// Applies pending migrations to the database at `database_url` without starting the server
+pub async fn migrate(config: &Config) -> Result<(), String> {
+    let url = config
+        .database_url
+        .as_deref()
+        .ok_or("No database configured, set database_url or DACHTERASSE_DATABASE_URL")?;
+    connect(url).await.map(|_| ())
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+async fn connect(url: &str) -> Result<database::LectureDatabase, String> {
+    let database = database::LectureDatabase::connect(url).await?;
+    database.migrate().await?;
+    Ok(database)
+}
+
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+async fn connect(url: &str) -> Result<dachterasse::asynch::sources::InMemoryDataSource, String> {
+    Err(format!(
+        "Cannot connect to {}, since the server was built without the postgres and sqlite features",
+        url
+    ))
+}
+
+#[cfg(feature = "shuttle")]
 #[shuttle_service::main]
-async fn init(#[shuttle_shared_db::Postgres] pool: PgPool) -> ShuttleRocket {
-    pool.execute(include_str!("../schema.sql"))
-        .await
-        .map_err(CustomError::new)?;
-    Ok(server::rocket(pool))
+async fn init(#[shuttle_shared_db::Postgres] pool: sqlx::PgPool) -> shuttle_service::ShuttleRocket {
+    use shuttle_service::error::CustomError;
+
+    let database = database::LectureDatabase::Postgres(pool);
+    database.migrate().await.map_err(CustomError::msg)?;
+    let config = Config::load(None).map_err(CustomError::msg)?;
+    Ok(server::rocket(Some(database), config))
 }
@@ -0,0 +1,113 @@
+use dachterasse::catalogue::CatalogueEntry;
+use dachterasse::query::Filter;
+use dachterasse::Lecture;
+use rocket::http::Header;
+use rocket::request::Request;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use serde::Serialize;
+
+/// Query parameters for filtering, sorting and paginating lecture lists,
+/// e.g. `?module=Vertiefungsgebiete&sort=-ects&limit=20&offset=40`
+#[derive(FromForm, Default, Debug)]
+pub struct LectureParams {
+    /// Only lectures which can be taken in any of these modules
+    pub module: Vec<String>,
+    /// Only lectures which can be taken in any of these categories, within the given modules if any
+    pub category: Vec<String>,
+    /// Only lectures whose title contains this text, ignoring case
+    pub title: Option<String>,
+    pub sort: Option<SortOrder>,
+    pub limit: Option<usize>,
+    #[field(default = 0)]
+    pub offset: usize,
+}
+
+#[derive(FromFormField, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    #[field(value = "title")]
+    Title,
+    #[field(value = "-title")]
+    TitleDescending,
+    /// Lectures without ECTS are listed last
+    #[field(value = "ects")]
+    Ects,
+    #[field(value = "-ects")]
+    EctsDescending,
+}
+
+impl LectureParams {
+    pub fn filter(&self) -> Filter {
+        let filter = Filter::placed_in(&self.module, &self.category);
+        match &self.title {
+            Some(title) => filter.and(Filter::title(title)),
+            None => filter,
+        }
+    }
+
+    /// Filters and sorts the lectures and returns the requested page
+    pub fn page(&self, lectures: Vec<Lecture>) -> Page<Lecture> {
+        let filter = self.filter();
+        let mut lectures = lectures
+            .into_iter()
+            .filter(|lecture| filter.matches(lecture))
+            .collect::<Vec<_>>();
+
+        if let Some(sort) = self.sort {
+            sort.sort(&mut lectures, |lecture| lecture);
+        }
+
+        Page::new(lectures, self.offset, self.limit)
+    }
+
+    /// Sorts the catalogue entries and returns the requested page. The entries have to be filtered
+    /// beforehand, since their lectures no longer contain the module placements.
+    pub fn page_entries(&self, mut entries: Vec<CatalogueEntry>) -> Page<CatalogueEntry> {
+        if let Some(sort) = self.sort {
+            sort.sort(&mut entries, |entry| &entry.lecture);
+        }
+
+        Page::new(entries, self.offset, self.limit)
+    }
+}
+
+impl SortOrder {
+    /// Sorts items by the lecture each of them refers to
+    pub fn sort<T>(self, items: &mut [T], lecture: impl Fn(&T) -> &Lecture) {
+        match self {
+            SortOrder::Title => items.sort_by(|a, b| lecture(a).title.cmp(&lecture(b).title)),
+            SortOrder::TitleDescending => items.sort_by(|a, b| lecture(b).title.cmp(&lecture(a).title)),
+            SortOrder::Ects => items.sort_by_key(|item| (lecture(item).ects.is_none(), lecture(item).ects)),
+            SortOrder::EctsDescending => items.sort_by_key(|item| {
+                let ects = lecture(item).ects;
+                (ects.is_none(), std::cmp::Reverse(ects))
+            }),
+        }
+    }
+}
+
+/// A page of a list, sent as a JSON array with the length of the whole list in the `X-Total-Count` header
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, offset: usize, limit: Option<usize>) -> Self {
+        let total = items.len();
+        let items = items
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Page { items, total }
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Page<T> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Json(self.items).respond_to(request)?;
+        response.set_header(Header::new("X-Total-Count", self.total.to_string()));
+        Ok(response)
+    }
+}
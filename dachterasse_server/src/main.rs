@@ -0,0 +1,40 @@
+//! Runs the server without Shuttle.
+//!
+//! Usage: `dachterasse_server [migrate] [CONFIG]`, where `CONFIG` is the path of a TOML config file. Without it,
+//! the default config file is read if it exists. Pending database migrations are applied on startup, `migrate`
+//! only applies them and exits. `DACHTERASSE_*` environment variables override the file,
+//! e.g. `DACHTERASSE_DATABASE_URL=sqlite://lectures.db`. Address and port are configured with Rocket's
+//! `ROCKET_ADDRESS` and `ROCKET_PORT`.
+
+use std::path::PathBuf;
+use std::process;
+
+use dachterasse::Config;
+
+#[rocket::main]
+async fn main() {
+    let mut args = std::env::args_os().skip(1).collect::<Vec<_>>();
+    let migrate_only = args.first().is_some_and(|arg| arg == "migrate");
+    if migrate_only {
+        args.remove(0);
+    }
+    let path = args.first().map(PathBuf::from);
+    let config = Config::load(path.as_deref()).unwrap_or_else(|err| exit(&err));
+
+    if migrate_only {
+        dachterasse_server::migrate(&config).await.unwrap_or_else(|err| exit(&err));
+        println!("Database is up to date");
+        return;
+    }
+
+    let rocket = dachterasse_server::rocket(config).await.unwrap_or_else(|err| exit(&err));
+
+    if let Err(err) = rocket.launch().await {
+        exit(&err.to_string());
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    process::exit(1)
+}
@@ -0,0 +1,348 @@
+use dachterasse::catalogue::{CatalogueEntry, Module};
+use dachterasse::{Lecture, StaticDegree};
+use rocket::serde::json::serde_json::{json, Map};
+use rocket::serde::json::{self, Value};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+
+use super::admin::{Refreshed, SourceReport};
+use super::error::Problem;
+
+/// Interactive documentation of the API, rendered by Swagger UI from the OpenAPI document
+pub const DOCS_UI: &str = include_str!("../static/docs.html");
+
+/// Swagger UI 5.17.14, vendored so that the documentation works without access to a CDN
+pub const SWAGGER_UI_CSS: &str = include_str!("../static/swagger-ui/swagger-ui.css");
+pub const SWAGGER_UI_JS: &str = include_str!("../static/swagger-ui/swagger-ui-bundle.js");
+
+/// Builder of an OpenAPI 3 document. The schemas of the types referenced by operations are collected
+/// in the document's components.
+pub struct ApiDoc {
+    generator: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl ApiDoc {
+    pub fn new() -> Self {
+        ApiDoc {
+            generator: SchemaSettings::openapi3().into_generator(),
+            paths: Map::new(),
+        }
+    }
+
+    /// A reference to the schema of `T`, e.g. `{"$ref": "#/components/schemas/Lecture"}`
+    pub fn schema<T: JsonSchema>(&mut self) -> Value {
+        json::to_value(self.generator.subschema_for::<T>()).unwrap_or_default()
+    }
+
+    /// Adds an operation. Path parameters are written in OpenAPI syntax, e.g. `/lectures/{degree}`.
+    pub fn operation(&mut self, method: &str, path: &str, operation: Value) {
+        let item = self.paths.entry(path.to_owned()).or_insert_with(|| json!({}));
+        item[method] = operation;
+    }
+
+    pub fn into_json(self) -> Value {
+        let schemas = self.generator.definitions();
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Dachterasse",
+                "description": "Lectures of the degree programs at HPI, as found on the lecture overview site",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": self.paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "admin": { "type": "http", "scheme": "bearer" },
+                },
+            },
+        })
+    }
+}
+
+impl Default for ApiDoc {
+    fn default() -> Self {
+        ApiDoc::new()
+    }
+}
+
+/// The OpenAPI document describing all routes of the server
+pub fn document() -> Value {
+    let mut doc = ApiDoc::new();
+    let problem = doc.schema::<Problem>();
+    let lectures = doc.schema::<Vec<Lecture>>();
+    let entries = doc.schema::<Vec<CatalogueEntry>>();
+    let entry = doc.schema::<CatalogueEntry>();
+    let degrees = doc.schema::<Vec<StaticDegree>>();
+    let degree = doc.schema::<StaticDegree>();
+    let modules = doc.schema::<Vec<Module>>();
+    let refreshed = doc.schema::<Refreshed>();
+    let sources = doc.schema::<Vec<SourceReport>>();
+
+    let not_found = error("No enabled degree or lecture with the given id exists", &problem);
+    let unavailable = error("Every data source failed to return lectures", &problem);
+    let unauthorized = error("The bearer token is missing or wrong", &problem);
+
+    let listing = listing_parameters();
+    let mut parameters = vec![json!({
+        "name": "degree",
+        "in": "query",
+        "description": "Comma-separated degree ids, e.g. `itse-ba,itse-ma`. Defaults to all enabled degrees.",
+        "schema": { "type": "string" },
+    })];
+    parameters.extend(listing.iter().cloned());
+    doc.operation(
+        "get",
+        "/lectures",
+        json!({
+            "summary": "Lectures of several degrees, each listed once with its placements in these degrees",
+            "tags": ["lectures"],
+            "parameters": parameters,
+            "responses": {
+                "200": page("Matching lectures", &entries),
+                "304": not_modified(),
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/lectures/id/{id}",
+        json!({
+            "summary": "A single lecture with its placements in all enabled degrees",
+            "tags": ["lectures"],
+            "parameters": [path_parameter("id", "Lecture id, derived from the lecture's URL")],
+            "responses": {
+                "200": cached("The lecture", json_content(&entry)),
+                "304": not_modified(),
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    let mut parameters = vec![degree_parameter()];
+    parameters.extend(listing);
+    doc.operation(
+        "get",
+        "/lectures/{degree}",
+        json!({
+            "summary": "Lectures of a degree, filtered, sorted and paginated",
+            "tags": ["lectures"],
+            "parameters": parameters,
+            "responses": {
+                "200": page("Matching lectures", &lectures),
+                "304": not_modified(),
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/lectures/{degree}/calendar.ics",
+        json!({
+            "summary": "Subscribable iCalendar feed with the schedules of all lectures of a degree",
+            "tags": ["lectures"],
+            "parameters": [degree_parameter()],
+            "responses": {
+                "200": cached("iCalendar feed", json!({ "text/calendar": { "schema": { "type": "string" } } })),
+                "304": not_modified(),
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/degrees",
+        json!({
+            "summary": "All enabled degrees",
+            "tags": ["degrees"],
+            "responses": { "200": { "description": "Enabled degrees", "content": json_content(&degrees) } },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/degrees/{degree}",
+        json!({
+            "summary": "A single degree",
+            "tags": ["degrees"],
+            "parameters": [degree_parameter()],
+            "responses": {
+                "200": { "description": "The degree", "content": json_content(&degree) },
+                "404": not_found,
+            },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/degrees/{degree}/modules",
+        json!({
+            "summary": "The modules of a degree with their categories, as found in the degree's lectures",
+            "tags": ["degrees"],
+            "parameters": [degree_parameter()],
+            "responses": {
+                "200": cached("Modules sorted by name", json_content(&modules)),
+                "304": not_modified(),
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    doc.operation(
+        "post",
+        "/admin/refresh/{degree}",
+        json!({
+            "summary": "Scrapes the lectures of a degree again and replaces the stored ones",
+            "tags": ["admin"],
+            "security": [{ "admin": [] }],
+            "parameters": [degree_parameter()],
+            "responses": {
+                "200": { "description": "Number of scraped lectures", "content": json_content(&refreshed) },
+                "401": unauthorized,
+                "404": not_found,
+                "503": unavailable,
+            },
+        }),
+    );
+    doc.operation(
+        "get",
+        "/admin/sources",
+        json!({
+            "summary": "Last success, last error and age of the stored lectures of every data source",
+            "tags": ["admin"],
+            "security": [{ "admin": [] }],
+            "responses": {
+                "200": { "description": "Health of the data sources", "content": json_content(&sources) },
+                "401": unauthorized,
+            },
+        }),
+    );
+    doc.operation(
+        "delete",
+        "/admin/cache/{degree}",
+        json!({
+            "summary": "Deletes the stored lectures of a degree, so that they are loaded again on the next request",
+            "tags": ["admin"],
+            "security": [{ "admin": [] }],
+            "parameters": [degree_parameter()],
+            "responses": {
+                "204": { "description": "Lectures were deleted" },
+                "401": unauthorized,
+                "404": not_found,
+            },
+        }),
+    );
+
+    doc.into_json()
+}
+
+fn path_parameter(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "path",
+        "required": true,
+        "description": description,
+        "schema": { "type": "string" },
+    })
+}
+
+fn degree_parameter() -> Value {
+    path_parameter("degree", "Degree id, e.g. `itse-ba`")
+}
+
+/// The query parameters of `LectureParams`
+fn listing_parameters() -> Vec<Value> {
+    let query = |name: &str, description: &str, schema: Value| {
+        json!({ "name": name, "in": "query", "description": description, "schema": schema })
+    };
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    vec![
+        query("module", "Only lectures which can be taken in any of these modules", strings.clone()),
+        query(
+            "category",
+            "Only lectures which can be taken in any of these categories, within the given modules if any",
+            strings,
+        ),
+        query(
+            "title",
+            "Only lectures whose title contains this text, ignoring case",
+            json!({ "type": "string" }),
+        ),
+        query(
+            "sort",
+            "Sort order, `-` sorts descending. Lectures without ECTS are listed last.",
+            json!({ "type": "string", "enum": ["title", "-title", "ects", "-ects"] }),
+        ),
+        query(
+            "limit",
+            "Maximum number of lectures to return",
+            json!({ "type": "integer", "minimum": 0 }),
+        ),
+        query(
+            "offset",
+            "Number of lectures to skip",
+            json!({ "type": "integer", "minimum": 0, "default": 0 }),
+        ),
+    ]
+}
+
+fn json_content(schema: &Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+fn cached(description: &str, content: Value) -> Value {
+    let header = |description: &str| json!({ "description": description, "schema": { "type": "string" } });
+    json!({
+        "description": description,
+        "headers": {
+            "ETag": header("Version of the lectures, to be sent in `If-None-Match`"),
+            "Last-Modified": header("Time at which the lectures last changed, to be sent in `If-Modified-Since`"),
+            "Cache-Control": header("How long the response may be cached"),
+        },
+        "content": content,
+    })
+}
+
+/// A list response of `Page`
+fn page(description: &str, schema: &Value) -> Value {
+    let mut response = cached(description, json_content(schema));
+    response["headers"]["X-Total-Count"] = json!({
+        "description": "Number of matching items on all pages",
+        "schema": { "type": "integer" },
+    });
+    response
+}
+
+fn not_modified() -> Value {
+    json!({ "description": "The lectures did not change since the version given in the request" })
+}
+
+fn error(description: &str, problem: &Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/problem+json": { "schema": problem } },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_only_existing_schemas() {
+        let document = document();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        for name in ["Lecture", "StaticDegree", "DegreeLevel", "Language", "CatalogueEntry", "Problem"] {
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+
+        let text = json::to_string(&document).unwrap();
+        for reference in text.split("\"$ref\":\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(schemas.contains_key(name), "dangling reference to {}", name);
+        }
+    }
+}
@@ -1,35 +1,142 @@
+use super::admin::{Admin, Refreshed, SourceReport};
+use super::caching::{Cached, Snapshots, Version};
 use super::cors::*;
-use super::database::LectureDatabase;
+use super::error::{default_catcher, unauthorized_catcher, ApiError, ApiResult};
+use super::listing::{LectureParams, Page};
+use super::openapi;
 use dachterasse::{
+    asynch::datasource::ReadWriteDataSource,
     asynch::repository::LectureRepository,
-    asynch::sources::{InMemoryDataSource, ScraperSource},
-    StaticDegree,
+    asynch::sources::{FSDataSource, ScraperSource},
+    Config, StaticDegree,
 };
-use dachterasse::{Degrees, Lecture};
+use dachterasse::catalogue::{module_tree, Catalogue, CatalogueEntry, Module};
+use dachterasse::export::{Exporter, IcsExporter};
+use dachterasse::query::Filter;
+use dachterasse::Lecture;
+use std::collections::HashMap;
+use std::time::SystemTime;
+use rocket::http::{ContentType, Status};
+use rocket::response::content::RawHtml;
+use rocket::serde::json::Value;
 use rocket::{serde::json::Json, State};
 use rocket::{Build, Rocket};
-use sqlx::PgPool;
 
-fn find_degree(id: &str) -> &'static StaticDegree {
-    Degrees::all()
-        .iter()
+fn find_degree(config: &Config, id: &str) -> ApiResult<&'static StaticDegree> {
+    config
+        .enabled_degrees()
+        .into_iter()
         .find(|d| d.id == id)
-        .expect("No degree program with this ID found!")
+        .ok_or_else(|| ApiError::UnknownDegree(id.to_owned()))
+}
+
+/// Serves the lectures of a degree from memory, loading them from the repository on the first request
+async fn load_lectures(
+    repository: &LectureRepository<'static>,
+    snapshots: &Snapshots,
+    degree: &'static StaticDegree,
+) -> ApiResult<(Vec<Lecture>, Version)> {
+    let snapshot = match snapshots.get(degree) {
+        Some(snapshot) => snapshot,
+        None => {
+            let loaded = repository.load(degree).await.map_err(ApiError::Unavailable)?;
+            snapshots.set(degree, loaded.lectures, loaded.modified.into())
+        }
+    };
+    Ok((snapshot.lectures, snapshot.version))
+}
+
+/// Loads the lectures of the given degrees which match the filter into one catalogue
+async fn load_catalogue(
+    repository: &LectureRepository<'static>,
+    snapshots: &Snapshots,
+    degrees: &[&'static StaticDegree],
+    filter: &Filter,
+) -> ApiResult<(Catalogue, Version)> {
+    let mut lectures = HashMap::new();
+    let mut degree_versions = Vec::new();
+    for &degree in degrees {
+        let (all, version) = load_lectures(repository, snapshots, degree).await?;
+        let matching = all.into_iter().filter(|lecture| filter.matches(lecture)).collect();
+        lectures.insert(degree, matching);
+        degree_versions.push(version);
+    }
+    Ok((Catalogue::from_lectures(&lectures), Version::combine(&degree_versions)))
 }
 
 #[route_module]
 mod lectures {
     use super::*;
 
-    #[get("/<degree>")]
-    async fn all(state: &State<LectureRepository<'static>>, degree: &str) -> Json<Vec<Lecture>> {
-        // TODO: Appropriate errors
-        let degree = find_degree(degree);
-        let lectures = state
-            .load_and_update(degree)
-            .await
-            .expect("Could not load lectures...");
-        Json(lectures)
+    /// Lectures of several degrees, e.g. `?degree=itse-ba,itse-ma`, each listed once with its placements
+    /// in the selected degrees. Without a `degree` parameter, all enabled degrees are included.
+    #[get("/?<degree>&<params..>")]
+    async fn of_degrees(
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: Option<&str>,
+        params: LectureParams,
+    ) -> ApiResult<Cached<Page<CatalogueEntry>>> {
+        let degrees = match degree {
+            Some(ids) => ids
+                .split(',')
+                .map(|id| find_degree(config, id.trim()))
+                .collect::<ApiResult<Vec<_>>>()?,
+            None => config.enabled_degrees(),
+        };
+        let (catalogue, version) = load_catalogue(state, snapshots, &degrees, &params.filter()).await?;
+        Ok(Cached::new(params.page_entries(catalogue.entries().to_vec()), version))
+    }
+
+    /// A single lecture with its placements in all enabled degrees
+    #[get("/id/<id>")]
+    async fn with_id(
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        id: &str,
+    ) -> ApiResult<Cached<Json<CatalogueEntry>>> {
+        let degrees = config.enabled_degrees();
+        let (catalogue, version) = load_catalogue(state, snapshots, &degrees, &Filter::All).await?;
+        let entry = catalogue
+            .get(id)
+            .cloned()
+            .ok_or_else(|| ApiError::UnknownLecture(id.to_owned()))?;
+        Ok(Cached::new(Json(entry), version))
+    }
+
+    /// Lectures of a degree, filtered, sorted and paginated according to the query parameters
+    #[get("/<degree>?<params..>")]
+    async fn all(
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: &str,
+        params: LectureParams,
+    ) -> ApiResult<Cached<Page<Lecture>>> {
+        let degree = find_degree(config, degree)?;
+        let (lectures, version) = load_lectures(state, snapshots, degree).await?;
+        Ok(Cached::new(params.page(lectures), version))
+    }
+
+    /// Subscribable iCalendar feed with the schedules of all lectures of a degree
+    #[get("/<degree>/calendar.ics", rank = 2)]
+    async fn calendar(
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: &str,
+    ) -> ApiResult<Cached<(ContentType, Vec<u8>)>> {
+        let degree = find_degree(config, degree)?;
+        let (lectures, version) = load_lectures(state, snapshots, degree).await?;
+
+        let mut calendar = Vec::new();
+        IcsExporter::new()
+            .name(degree.name)
+            .export(&lectures, &mut calendar)
+            .map_err(|err| ApiError::Other(Status::InternalServerError, err.to_string()))?;
+        Ok(Cached::new((ContentType::new("text", "calendar"), calendar), version))
     }
 }
 
@@ -38,25 +145,452 @@ mod degrees {
     use super::*;
 
     #[get("/")]
-    async fn all() -> Json<&'static [StaticDegree]> {
-        Json(Degrees::all())
+    async fn all(config: &State<Config>) -> Json<Vec<&'static StaticDegree>> {
+        Json(config.enabled_degrees())
     }
 
     #[get("/<degree>")]
-    async fn with_id(degree: &str) -> Json<&'static StaticDegree> {
-        Json(find_degree(degree))
+    async fn with_id(config: &State<Config>, degree: &str) -> ApiResult<Json<&'static StaticDegree>> {
+        Ok(Json(find_degree(config, degree)?))
+    }
+
+    /// The modules of a degree with their categories, as found in the degree's lectures
+    #[get("/<degree>/modules")]
+    async fn modules(
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: &str,
+    ) -> ApiResult<Cached<Json<Vec<Module>>>> {
+        let degree = find_degree(config, degree)?;
+        let (lectures, version) = load_lectures(state, snapshots, degree).await?;
+        Ok(Cached::new(Json(module_tree(&lectures)), version))
     }
 }
 
-pub fn rocket(_pool: PgPool) -> Rocket<Build> {
-    let repository = LectureRepository::new()
-        .source(InMemoryDataSource::new())
-        // .source(LectureDatabase::new(pool))
-        .readonly_source(ScraperSource::new());
+#[route_module]
+mod admin {
+    use super::*;
+
+    /// Scrapes the lectures of a degree again and replaces the stored ones
+    #[post("/refresh/<degree>")]
+    async fn refresh(
+        _admin: Admin,
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: &str,
+    ) -> ApiResult<Json<Refreshed>> {
+        let degree = find_degree(config, degree)?;
+        let lectures = state.refresh(degree).await.map_err(ApiError::Unavailable)?;
+        let snapshot = snapshots.set(degree, lectures, SystemTime::now());
+        Ok(Json(Refreshed {
+            degree: degree.id,
+            lectures: snapshot.lectures.len(),
+        }))
+    }
+
+    /// Last success, last error and age of the stored lectures of every data source
+    #[get("/sources")]
+    async fn sources(_admin: Admin, state: &State<LectureRepository<'static>>) -> Json<Vec<SourceReport>> {
+        Json(state.health().into_iter().map(SourceReport::from).collect())
+    }
 
+    /// Deletes the stored lectures of a degree, so that they are loaded again on the next request
+    #[delete("/cache/<degree>")]
+    async fn clear(
+        _admin: Admin,
+        state: &State<LectureRepository<'static>>,
+        snapshots: &State<Snapshots>,
+        config: &State<Config>,
+        degree: &str,
+    ) -> ApiResult<Status> {
+        let degree = find_degree(config, degree)?;
+        snapshots.remove(degree);
+        state
+            .clear(degree)
+            .await
+            .map_err(|err| ApiError::Other(Status::InternalServerError, err))?;
+        Ok(Status::NoContent)
+    }
+}
+
+#[route_module]
+mod documentation {
+    use super::*;
+
+    /// OpenAPI 3 document describing all routes
+    #[get("/openapi.json")]
+    fn document() -> Json<Value> {
+        Json(openapi::document())
+    }
+
+    /// Interactive API documentation, if enabled in the config
+    #[get("/docs")]
+    fn ui(config: &State<Config>) -> Option<RawHtml<&'static str>> {
+        config.api_docs.then_some(RawHtml(openapi::DOCS_UI))
+    }
+
+    /// Stylesheet and script of Swagger UI, which renders the documentation
+    #[get("/docs/<asset>")]
+    fn asset(config: &State<Config>, asset: &str) -> Option<(ContentType, &'static str)> {
+        if !config.api_docs {
+            return None;
+        }
+        match asset {
+            "swagger-ui.css" => Some((ContentType::CSS, openapi::SWAGGER_UI_CSS)),
+            "swagger-ui-bundle.js" => Some((ContentType::JavaScript, openapi::SWAGGER_UI_JS)),
+            _ => None,
+        }
+    }
+}
+
+/// Builds the server. Lectures are scraped according to the config and kept in memory, in the
+/// database if one is given and, if a cache directory is configured, on disk.
+pub fn rocket(database: Option<impl ReadWriteDataSource + 'static>, config: Config) -> Rocket<Build> {
+    let mut repository = LectureRepository::new();
+    if let Some(database) = database {
+        repository.add_source(database);
+    }
+    if let Some(path) = config.get_cache_path() {
+        let mut cache = FSDataSource::new(path.to_string());
+        if let Some(ttl) = config.cache_ttl {
+            cache = cache.ttl(ttl);
+        }
+        repository.add_source(cache);
+    }
+    if !config.offline {
+        repository.add_readonly_source(ScraperSource::from_config(&config));
+    }
+
+    build(repository, config)
+}
+
+/// Builds the server serving lectures from the given repository
+pub fn build(repository: LectureRepository<'static>, config: Config) -> Rocket<Build> {
     rocket::build()
         .manage(repository)
+        .manage(config)
+        .manage(Snapshots::new())
+        .register("/", catchers![default_catcher, unauthorized_catcher])
         .mount("/lectures", module!(lectures))
         .mount("/degrees", module!(degrees))
+        .mount("/admin", module!(admin))
+        .mount("/", module!(documentation))
         .attach(CORS)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use dachterasse::asynch::datasource::{LoadResult, ReadOnlyDataSource, SaveResult};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use dachterasse::Degrees;
+    use rocket::http::Header;
+    use rocket::local::blocking::Client;
+
+    fn lecture(title: &str, module: &str, ects: u8) -> Lecture {
+        Lecture {
+            title: title.to_owned(),
+            url: format!("https://hpi.de/studium/{}.html", title),
+            categories: Some([(module.to_owned(), vec![])].into()),
+            ects: Some(ects),
+            ..Default::default()
+        }
+    }
+
+    struct StaticSource;
+
+    #[async_trait]
+    impl ReadOnlyDataSource for StaticSource {
+        async fn load_lectures(&self, _degree: &'static StaticDegree) -> LoadResult {
+            Ok(vec![
+                lecture("Datenbanksysteme I", "Softwarebasissysteme", 6),
+                lecture("Compilerbau", "Vertiefungsgebiete", 3),
+                lecture("Datenbanksysteme II", "Vertiefungsgebiete", 6),
+            ])
+        }
+    }
+
+    /// A source which counts how often its lectures were loaded and saved and which knows when they were written
+    #[derive(Default, Clone)]
+    struct DatedSource {
+        loads: Arc<AtomicUsize>,
+        saves: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ReadOnlyDataSource for DatedSource {
+        async fn load_lectures(&self, degree: &'static StaticDegree) -> LoadResult {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            StaticSource.load_lectures(degree).await
+        }
+
+        async fn modified(&self, _degree: &'static StaticDegree) -> Option<DateTime<Utc>> {
+            Utc.with_ymd_and_hms(2023, 10, 1, 12, 0, 0).single()
+        }
+    }
+
+    #[async_trait]
+    impl ReadWriteDataSource for DatedSource {
+        async fn save_lectures(&self, _degree: &'static StaticDegree, _lectures: &[Lecture]) -> SaveResult {
+            self.saves.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct FailingSource;
+
+    #[async_trait]
+    impl ReadOnlyDataSource for FailingSource {
+        async fn load_lectures(&self, _degree: &'static StaticDegree) -> LoadResult {
+            Err(String::from("Site unreachable"))
+        }
+    }
+
+    fn client(source: impl ReadOnlyDataSource + 'static, config: Config) -> Client {
+        let repository = LectureRepository::new().readonly_source(source);
+        Client::tracked(build(repository, config)).expect("valid rocket instance")
+    }
+
+    #[test]
+    fn serves_lectures() {
+        let client = client(StaticSource, Config::new());
+        let response = client.get("/lectures/itse-ba").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let lectures = response.into_json::<Vec<Lecture>>().unwrap();
+        assert_eq!(lectures[0].title, "Datenbanksysteme I");
+    }
+
+    #[test]
+    fn filters_sorts_and_paginates_lectures() {
+        let client = client(StaticSource, Config::new());
+        let response = client
+            .get("/lectures/itse-ba?module=vertiefungsgebiete&sort=-title&limit=1&offset=1")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Total-Count"), Some("2"));
+        let lectures = response.into_json::<Vec<Lecture>>().unwrap();
+        assert_eq!(lectures.len(), 1);
+        assert_eq!(lectures[0].title, "Compilerbau");
+    }
+
+    #[test]
+    fn merges_lectures_of_several_degrees() {
+        let client = client(StaticSource, Config::new());
+        let response = client
+            .get("/lectures?degree=itse-ba,itse-ma&module=Vertiefungsgebiete&sort=title")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Total-Count"), Some("2"));
+        let entries = response.into_json::<rocket::serde::json::Value>().unwrap();
+        assert_eq!(entries[0]["lecture"]["title"], "Compilerbau");
+        assert_eq!(entries[0]["placements"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn serves_lecture_by_id_and_module_tree() {
+        let client = client(StaticSource, Config::new().degrees(&[&Degrees::ITSE_BA, &Degrees::ITSE_MA]));
+
+        let lecture = client.get("/lectures/id/Compilerbau").dispatch();
+        assert_eq!(lecture.status(), Status::Ok);
+        let entry = lecture.into_json::<rocket::serde::json::Value>().unwrap();
+        assert_eq!(entry["placements"][1]["degree"]["id"], "itse-ma");
+
+        let modules = client.get("/degrees/itse-ba/modules").dispatch();
+        let modules = modules.into_json::<Vec<rocket::serde::json::Value>>().unwrap();
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[1]["name"], "Vertiefungsgebiete");
+        assert_eq!(modules[1]["lectures"], 2);
+
+        let unknown = client.get("/lectures/id/unknown").dispatch();
+        assert_eq!(unknown.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn unchanged_lectures_are_not_modified() {
+        let client = client(StaticSource, Config::new());
+        let response = client.get("/lectures/itse-ba").dispatch();
+        let etag = response.headers().get_one("ETag").unwrap().to_owned();
+        let modified = response.headers().get_one("Last-Modified").unwrap().to_owned();
+        assert!(response.headers().get_one("Cache-Control").is_some());
+
+        let by_etag = client
+            .get("/lectures/itse-ba")
+            .header(Header::new("If-None-Match", etag.clone()))
+            .dispatch();
+        assert_eq!(by_etag.status(), Status::NotModified);
+        assert_eq!(by_etag.headers().get_one("ETag"), Some(etag.as_str()));
+
+        let by_date = client
+            .get("/lectures/itse-ba")
+            .header(Header::new("If-Modified-Since", modified))
+            .dispatch();
+        assert_eq!(by_date.status(), Status::NotModified);
+
+        let outdated = client
+            .get("/lectures/itse-ba")
+            .header(Header::new("If-None-Match", "\"outdated\""))
+            .dispatch();
+        assert_eq!(outdated.status(), Status::Ok);
+    }
+
+    #[test]
+    fn versions_are_computed_once_from_the_time_lectures_were_written() {
+        let source = DatedSource::default();
+        let repository = LectureRepository::new().source(source.clone());
+        let client = Client::tracked(build(repository, Config::new())).unwrap();
+
+        let first = client.get("/lectures/itse-ba").dispatch();
+        assert_eq!(first.headers().get_one("Last-Modified"), Some("Sun, 01 Oct 2023 12:00:00 GMT"));
+        let etag = first.headers().get_one("ETag").unwrap().to_owned();
+
+        let second = client.get("/lectures/itse-ba").dispatch();
+        assert_eq!(second.headers().get_one("ETag"), Some(etag.as_str()));
+        assert_eq!(source.loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn lectures_are_not_written_back_to_their_source() {
+        let cache = DatedSource::default();
+        let repository = LectureRepository::new().source(cache.clone()).readonly_source(StaticSource);
+        let client = Client::tracked(build(repository, Config::new().admin_token("secret"))).unwrap();
+
+        for degree in ["itse-ba", "itse-ma", "itse-ba"] {
+            let response = client.get(format!("/lectures/{}", degree)).dispatch();
+            assert_eq!(response.status(), Status::Ok);
+        }
+        assert_eq!(cache.saves.load(Ordering::SeqCst), 0);
+
+        client
+            .post("/admin/refresh/itse-ba")
+            .header(Header::new("Authorization", "Bearer secret"))
+            .dispatch();
+        assert_eq!(cache.saves.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn admin_endpoints_require_token() {
+        let disabled = client(StaticSource, Config::new());
+        assert_eq!(disabled.get("/admin/sources").dispatch().status(), Status::NotFound);
+
+        let client = client(StaticSource, Config::new().admin_token("secret"));
+
+        let anonymous = client.post("/admin/refresh/itse-ba").dispatch();
+        assert_eq!(anonymous.status(), Status::Unauthorized);
+        assert_eq!(anonymous.headers().get_one("WWW-Authenticate"), Some("Bearer"));
+
+        let refreshed = client
+            .post("/admin/refresh/itse-ba")
+            .header(Header::new("Authorization", "Bearer secret"))
+            .dispatch();
+        assert_eq!(refreshed.status(), Status::Ok);
+
+        let sources = client
+            .get("/admin/sources")
+            .header(Header::new("Authorization", "Bearer secret"))
+            .dispatch()
+            .into_json::<Vec<rocket::serde::json::Value>>()
+            .unwrap();
+        assert_eq!(sources[0]["name"], "StaticSource");
+        assert!(sources[0]["last_success"].is_string());
+        assert!(sources[0]["age"]["itse-ba"].is_number());
+
+        let cleared = client
+            .delete("/admin/cache/itse-ba")
+            .header(Header::new("Authorization", "Bearer secret"))
+            .dispatch();
+        assert_eq!(cleared.status(), Status::NoContent);
+    }
+
+    #[test]
+    fn unknown_degree_is_not_found() {
+        let client = client(StaticSource, Config::new().degrees(&[&Degrees::ITSE_BA]));
+
+        for uri in ["/lectures/unknown", "/degrees/itse-ma", "/nothing/here"] {
+            let response = client.get(uri).dispatch();
+            assert_eq!(response.status(), Status::NotFound, "{}", uri);
+            assert_eq!(
+                response.content_type(),
+                Some(ContentType::new("application", "problem+json"))
+            );
+        }
+    }
+
+    #[test]
+    fn failing_sources_are_unavailable() {
+        let client = client(FailingSource, Config::new());
+        let response = client.get("/lectures/itse-ba/calendar.ics").dispatch();
+
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        assert!(response.headers().get_one("Retry-After").is_some());
+        let problem = response.into_json::<rocket::serde::json::Value>().unwrap();
+        assert_eq!(problem["status"], 503);
+    }
+
+    #[test]
+    fn serves_openapi_document_and_optional_docs() {
+        let without_docs = client(StaticSource, Config::new());
+        let document = without_docs.get("/openapi.json").dispatch().into_json::<Value>().unwrap();
+        assert_eq!(document["openapi"], "3.0.3");
+        assert!(document["paths"]["/lectures/{degree}"]["get"].is_object());
+        assert_eq!(without_docs.get("/docs").dispatch().status(), Status::NotFound);
+
+        let with_docs = client(StaticSource, Config::new().api_docs());
+        let docs = with_docs.get("/docs").dispatch();
+        assert_eq!(docs.status(), Status::Ok);
+        assert_eq!(docs.content_type(), Some(ContentType::HTML));
+        let script = with_docs.get("/docs/swagger-ui-bundle.js").dispatch();
+        assert_eq!(script.content_type(), Some(ContentType::JavaScript));
+        assert_eq!(without_docs.get("/docs/swagger-ui.css").dispatch().status(), Status::NotFound);
+    }
+
+    #[test]
+    fn documents_every_route() {
+        let document = openapi::document();
+        let rocket = build(LectureRepository::new(), Config::new());
+        let undocumented = ["/openapi.json", "/docs", "/docs/{asset}"];
+
+        let mut documented = 0;
+        for route in rocket.routes() {
+            let path = route
+                .uri
+                .path()
+                .split('/')
+                .map(|segment| match segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                    Some(parameter) => format!("{{{}}}", parameter),
+                    None => segment.to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let path = match path.trim_end_matches('/') {
+                "" => "/",
+                path => path,
+            };
+            if undocumented.contains(&path) {
+                continue;
+            }
+
+            let method = route.method.as_str().to_lowercase();
+            assert!(
+                document["paths"][path][&method].is_object(),
+                "{} {} is missing in the OpenAPI document",
+                method,
+                path
+            );
+            documented += 1;
+        }
+        let operations = document["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|item| item.as_object().unwrap().len())
+            .sum::<usize>();
+        assert_eq!(documented, operations, "the OpenAPI document describes routes which are not mounted");
+    }
+}